@@ -5,10 +5,10 @@ use std::{
     sync::Mutex,
 };
 
-use anyhow::Context;
+use anyhow::{ensure, Context};
 use regex::Regex;
 use rs4a_vapix::{
-    http::{HttpClient, Request, Response},
+    http::{Body, HttpClient, Request, Response},
     Client,
 };
 
@@ -20,7 +20,7 @@ fn request_checksum(request: &Request) -> u64 {
     let mut hasher = DefaultHasher::new();
     request.method.hash(&mut hasher);
     request.path.hash(&mut hasher);
-    if let Some(body) = &request.body {
+    if let Some(body) = request.body.as_ref().and_then(Body::as_bytes) {
         hasher.write(body);
         hasher.write_u8(0xff);
     }
@@ -160,6 +160,10 @@ impl std::fmt::Debug for CassetteClient {
 
 impl HttpClient for CassetteClient {
     async fn execute(&self, request: Request) -> Result<Response, anyhow::Error> {
+        ensure!(
+            !matches!(request.body, Some(Body::Stream(_))),
+            "Streamed request bodies cannot be recorded"
+        );
         let checksum = request_checksum(&request);
         let serialized_request = serialize_request(&request);
         match &self.inner {
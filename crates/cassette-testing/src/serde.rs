@@ -2,14 +2,14 @@ use std::str::FromStr;
 
 use anyhow::Context;
 use reqwest::StatusCode;
-use rs4a_vapix::http::{Request, Response};
+use rs4a_vapix::http::{Body, Request, Response};
 
 pub(crate) fn serialize_request(request: &Request) -> String {
     let mut content = format!("{} {}\n", request.method, request.path);
     if let Some(content_type) = &request.content_type {
         content.push_str(&format!("Content-Type: {content_type}\n"));
     }
-    if let Some(body) = &request.body {
+    if let Some(body) = request.body.as_ref().and_then(Body::as_bytes) {
         content.push_str(&format!("\n{}", String::from_utf8_lossy(body)));
     }
     content
@@ -131,6 +131,8 @@ impl InstallCommand {
                 command: rs4a_device_manager::Commands::Init(rs4a_device_manager::InitCommand {
                     netloc,
                     profile,
+                    ssh_authorized_key: None,
+                    network: rs4a_device_manager::NetworkArgs::default(),
                 }),
             };
             init_cli.exec().await?;
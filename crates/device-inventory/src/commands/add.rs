@@ -1,3 +1,8 @@
+use anyhow::Context;
+use rs4a_vapix::{
+    tls::{fetch_certificate_fingerprint, CertificateFingerprint},
+    SchemePolicy,
+};
 use url::Host;
 
 use crate::{
@@ -28,9 +33,23 @@ pub struct AddCommand {
     /// SSH port to use, if different from default
     #[clap(long)]
     ssh_port: Option<u16>,
+    /// RTSP port to use, if different from default
+    #[clap(long)]
+    rtsp_port: Option<u16>,
     /// The device uses a self-signed HTTPS certificate.
     #[clap(long)]
     https_self_signed: bool,
+    /// The SHA-256 fingerprint of the HTTPS certificate that the device must present.
+    #[clap(long, conflicts_with = "pin_certificate")]
+    https_fingerprint: Option<CertificateFingerprint>,
+    /// Fetch and pin the HTTPS certificate that the device presents now.
+    ///
+    /// Only use this on a network that is trusted at the time of adding the device.
+    #[clap(long)]
+    pin_certificate: bool,
+    /// Which schemes clients may use; one of `secure-only`, `plain-only` or `prefer-secure`.
+    #[clap(long)]
+    scheme_policy: Option<SchemePolicy>,
 }
 
 impl AddCommand {
@@ -43,8 +62,20 @@ impl AddCommand {
             http_port,
             https_port,
             ssh_port,
+            rtsp_port,
             https_self_signed,
+            https_fingerprint,
+            pin_certificate,
+            scheme_policy,
         } = self;
+        let https_fingerprint = match pin_certificate {
+            true => Some(
+                fetch_certificate_fingerprint(&host, https_port)
+                    .await
+                    .context("Could not fetch certificate to pin")?,
+            ),
+            false => https_fingerprint,
+        };
         let mut devices = db.read_devices()?;
         devices.insert(
             alias,
@@ -55,9 +86,12 @@ impl AddCommand {
                 http_port,
                 https_port,
                 ssh_port,
+                rtsp_port,
                 // TODO: Fetch from device
                 model: None,
                 https_self_signed,
+                https_fingerprint: https_fingerprint.map(|f| f.to_string()),
+                scheme_policy: scheme_policy.map(|p| p.to_string()),
             },
         );
         db.write_devices(&devices)?;
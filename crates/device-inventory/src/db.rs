@@ -22,9 +22,17 @@ pub struct Device {
     pub http_port: Option<u16>,
     pub https_port: Option<u16>,
     pub ssh_port: Option<u16>,
+    #[serde(default)]
+    pub rtsp_port: Option<u16>,
     pub model: Option<String>,
     #[serde(default = "default_true")]
     pub https_self_signed: bool,
+    /// The SHA-256 fingerprint of the HTTPS certificate, if pinned.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub https_fingerprint: Option<String>,
+    /// Which schemes clients may use, if different from the default.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scheme_policy: Option<String>,
 }
 
 impl From<rs4a_dut::Device> for Device {
@@ -36,7 +44,10 @@ impl From<rs4a_dut::Device> for Device {
             http_port,
             https_port,
             ssh_port,
+            rtsp_port,
             https_self_signed,
+            https_fingerprint,
+            scheme_policy,
         } = value;
         Self {
             host,
@@ -45,8 +56,11 @@ impl From<rs4a_dut::Device> for Device {
             http_port,
             https_port,
             ssh_port,
+            rtsp_port,
             model: None,
             https_self_signed,
+            https_fingerprint,
+            scheme_policy,
         }
     }
 }
@@ -60,8 +74,11 @@ impl From<Device> for rs4a_dut::Device {
             http_port,
             https_port,
             ssh_port,
+            rtsp_port,
             model: _,
             https_self_signed,
+            https_fingerprint,
+            scheme_policy,
         } = value;
         rs4a_dut::Device {
             host,
@@ -70,7 +87,10 @@ impl From<Device> for rs4a_dut::Device {
             http_port,
             https_port,
             ssh_port,
+            rtsp_port,
             https_self_signed,
+            https_fingerprint,
+            scheme_policy,
         }
     }
 }
@@ -63,6 +63,7 @@ pub fn device_from_loan(loan: Loan) -> (String, Device) {
     let http_port = loan.http_port();
     let https_port = loan.https_port();
     let ssh_port = loan.ssh_port();
+    let rtsp_port = loan.rtsp_port();
     let Loan {
         username,
         password,
@@ -86,7 +87,10 @@ pub fn device_from_loan(loan: Loan) -> (String, Device) {
             http_port: Some(http_port),
             https_port: Some(https_port),
             ssh_port: Some(ssh_port),
+            rtsp_port: Some(rtsp_port),
             https_self_signed: true,
+            https_fingerprint: None,
+            scheme_policy: None,
         },
     )
 }
@@ -48,8 +48,13 @@ fn can_export_loans() {
         .output()
         .unwrap();
     let mut exports = HashMap::new();
+    let mut unsets = Vec::new();
     for line in output.stdout.lines() {
         let line = line.unwrap();
+        if let Some(k) = line.strip_prefix("unset ") {
+            unsets.push(k.to_string());
+            continue;
+        }
         let (k, v) = line
             .strip_prefix("export ")
             .unwrap()
@@ -64,4 +69,13 @@ fn can_export_loans() {
     assert_eq!(exports["AXIS_DEVICE_HTTP_PORT"], "12051");
     assert_eq!(exports["AXIS_DEVICE_HTTPS_PORT"], "42051");
     assert_eq!(exports["AXIS_DEVICE_HTTPS_SELF_SIGNED"], "1");
+    // Not in the test data since it predates these fields
+    assert_eq!(
+        unsets,
+        [
+            "AXIS_DEVICE_RTSP_PORT",
+            "AXIS_DEVICE_HTTPS_FINGERPRINT",
+            "AXIS_DEVICE_SCHEME_POLICY"
+        ]
+    );
 }
@@ -1,14 +1,23 @@
+use std::path::PathBuf;
+
 use anyhow::{bail, Context};
 use log::{debug, info, warn};
 use rs4a_vapix::{
     apis::{
         applications_config,
         basic_device_info_1::GetAllUnrestrictedPropertiesRequest,
-        network_settings_1::{SetGlobalProxyConfigurationData, SetGlobalProxyConfigurationRequest},
+        discover::DiscoverRequest,
+        network_settings_1::{
+            GetNetworkInfoRequest, SetGlobalProxyConfigurationData,
+            SetGlobalProxyConfigurationRequest, SetHostnameConfigurationRequest,
+            SetResolverConfigurationRequest,
+        },
+        ntp_1::SetNtpClientConfigurationRequest,
         parameter_management, pwdgrp,
         pwdgrp::AddUserRequest,
-        ssh_1,
+        ssh_1, ssh_2,
         system_ready_1::SystemReadyRequest,
+        time_1::SetDateTimeRequest,
     },
     protocol_helpers::http::Error,
     Client,
@@ -39,15 +48,97 @@ pub struct InitCommand {
     pub netloc: Netloc,
     #[arg(long, default_value_t)]
     pub profile: Profile,
+    /// Public key to authorize for the `ssh` user, e.g. `~/.ssh/id_ed25519.pub`.
+    ///
+    /// Requires firmware that implements v2 of the SSH management API.
+    #[arg(long, env = "AXIS_DEVICE_SSH_AUTHORIZED_KEY")]
+    pub ssh_authorized_key: Option<PathBuf>,
+    #[command(flatten)]
+    pub network: NetworkArgs,
 }
 
 impl InitCommand {
     pub async fn exec(self) -> anyhow::Result<String> {
-        initialize(&self.netloc, &self.profile).await?;
+        let ssh_authorized_key = read_authorized_key(self.ssh_authorized_key.as_ref()).await?;
+        initialize(
+            &self.netloc,
+            &self.profile,
+            ssh_authorized_key.as_deref(),
+            &self.network,
+        )
+        .await?;
         Ok(String::new())
     }
 }
 
+#[derive(Clone, Debug, Default, clap::Args)]
+pub struct NetworkArgs {
+    /// Static hostname to give the device instead of the one provided by DHCP.
+    #[arg(long)]
+    pub hostname: Option<String>,
+    /// Static name server to use instead of those provided by DHCP.
+    #[arg(long = "name-server")]
+    pub name_servers: Vec<String>,
+    /// NTP server to synchronize the clock with instead of those provided by DHCP.
+    #[arg(long = "ntp-server")]
+    pub ntp_servers: Vec<String>,
+    /// Set the clock of the device to that of this host.
+    #[arg(long)]
+    pub set_clock: bool,
+}
+
+async fn configure_network(client: &Client, network: &NetworkArgs) -> anyhow::Result<()> {
+    if let Some(hostname) = &network.hostname {
+        info!("Setting hostname to {hostname}...");
+        SetHostnameConfigurationRequest::static_hostname(hostname)
+            .send(client)
+            .await
+            .context("Failed to set hostname")?;
+    }
+
+    if !network.name_servers.is_empty() {
+        info!("Setting name servers to {:?}...", network.name_servers);
+        let data = GetNetworkInfoRequest::new()
+            .send(client)
+            .await
+            .context("Failed to query resolver configuration")?;
+        SetResolverConfigurationRequest::from_resolver(&data.system.resolver)
+            .use_dhcp_resolver_info(false)
+            .static_name_servers(network.name_servers.clone())
+            .send(client)
+            .await
+            .context("Failed to set name servers")?;
+    }
+
+    if network.set_clock {
+        let now = chrono::Utc::now();
+        info!("Setting clock to {now}...");
+        SetDateTimeRequest::new(now)
+            .send(client)
+            .await
+            .context("Failed to set clock")?;
+    }
+
+    if !network.ntp_servers.is_empty() {
+        info!("Setting NTP servers to {:?}...", network.ntp_servers);
+        SetNtpClientConfigurationRequest::static_servers(network.ntp_servers.clone())
+            .send(client)
+            .await
+            .context("Failed to set NTP servers")?;
+    }
+    Ok(())
+}
+
+pub async fn read_authorized_key(path: Option<&PathBuf>) -> anyhow::Result<Option<String>> {
+    let Some(path) = path else {
+        return Ok(None);
+    };
+    let key = tokio::fs::read_to_string(path)
+        .await
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    Ok(Some(key.trim().to_string()))
+}
+
 fn parse_firmware_version(s: &str) -> anyhow::Result<Version> {
     let mut parts = s.splitn(4, '.');
     let major = parts.next().unwrap_or_default().parse()?;
@@ -61,10 +152,18 @@ async fn apply_setup_profile(client: &rs4a_vapix::Client, version: &Version) ->
         *version >= Version::new(11, 2, 0) && *version < Version::new(13, 0, 0);
 
     if allows_unsigned_toggle {
-        info!("Allowing unsigned ACAP applications...");
-        applications_config::ApplicationConfigRequest::allow_unsigned(true)
+        let allowed = applications_config::ApplicationConfigRequest::get("AllowUnsigned")
             .send(client)
-            .await?;
+            .await?
+            .bool_value("AllowUnsigned")?;
+        if allowed == Some(true) {
+            info!("Unsigned ACAP applications are already allowed");
+        } else {
+            info!("Allowing unsigned ACAP applications (was {allowed:?})...");
+            applications_config::ApplicationConfigRequest::allow_unsigned(true)
+                .send(client)
+                .await?;
+        }
     } else {
         debug!("Skipping AllowUnsigned (not applicable for firmware {version})");
     }
@@ -117,7 +216,41 @@ async fn create_user(netloc: &Netloc, anonymous_client: &Client) -> anyhow::Resu
     Ok(())
 }
 
-pub async fn initialize(netloc: &Netloc, profile: &Profile) -> anyhow::Result<()> {
+/// Add the `ssh` user, using v2 of the SSH management API only if a key must be authorized.
+async fn add_ssh_user(
+    client: &Client,
+    password: &str,
+    authorized_key: Option<&str>,
+) -> anyhow::Result<()> {
+    let Some(key) = authorized_key else {
+        ssh_1::AddUserRequest::new("ssh", password)
+            .send(client)
+            .await?;
+        return Ok(());
+    };
+
+    let supports_v2 = DiscoverRequest
+        .send(client)
+        .await
+        .context("Failed to discover configuration APIs")?
+        .supports("ssh", "v2");
+    if !supports_v2 {
+        bail!("Cannot authorize SSH key (SSH management v2 is not supported)");
+    }
+    ssh_2::AddUserRequest::new("ssh")
+        .password(password)
+        .authorized_key(key)
+        .send(client)
+        .await?;
+    Ok(())
+}
+
+pub async fn initialize(
+    netloc: &Netloc,
+    profile: &Profile,
+    ssh_authorized_key: Option<&str>,
+    network: &NetworkArgs,
+) -> anyhow::Result<()> {
     info!("Initializing device...");
 
     let anonymous_client = netloc.connect_anonymous().await?;
@@ -149,10 +282,11 @@ pub async fn initialize(netloc: &Netloc, profile: &Profile) -> anyhow::Result<()
 
     if version >= Version::new(11, 0, 0) {
         info!("Adding SSH user...");
-        ssh_1::AddUserRequest::new("ssh", &netloc.pass)
-            .send(&client)
+        add_ssh_user(&client, &netloc.pass, ssh_authorized_key)
             .await
             .context("Failed to add SSH user")?;
+    } else if ssh_authorized_key.is_some() {
+        bail!("Cannot authorize SSH key (not supported on firmware {version})");
     } else {
         debug!("Skipping SSH user creation (not supported on firmware {version})");
     }
@@ -165,6 +299,8 @@ pub async fn initialize(netloc: &Netloc, profile: &Profile) -> anyhow::Result<()
 
     apply_setup_profile(&client, &version).await?;
 
+    configure_network(&client, network).await?;
+
     if matches!(profile, Profile::Vlt) {
         // FIXME: Skip when not supported by firmware
         info!("Setting global proxy configuration...");
@@ -1,4 +1,6 @@
-use super::init::Profile;
+use std::path::PathBuf;
+
+use super::init::{NetworkArgs, Profile};
 use crate::Netloc;
 
 #[derive(Clone, Debug, clap::Args)]
@@ -7,12 +9,25 @@ pub struct ReinitCommand {
     pub netloc: Netloc,
     #[arg(long, default_value_t)]
     pub profile: Profile,
+    /// Public key to authorize for the `ssh` user, e.g. `~/.ssh/id_ed25519.pub`.
+    #[arg(long, env = "AXIS_DEVICE_SSH_AUTHORIZED_KEY")]
+    pub ssh_authorized_key: Option<PathBuf>,
+    #[command(flatten)]
+    pub network: NetworkArgs,
 }
 
 impl ReinitCommand {
     pub async fn exec(self) -> anyhow::Result<String> {
+        let ssh_authorized_key =
+            super::init::read_authorized_key(self.ssh_authorized_key.as_ref()).await?;
         super::restore::restore(&self.netloc).await?;
-        super::init::initialize(&self.netloc, &self.profile).await?;
+        super::init::initialize(
+            &self.netloc,
+            &self.profile,
+            ssh_authorized_key.as_deref(),
+            &self.network,
+        )
+        .await?;
         Ok(String::new())
     }
 }
@@ -3,7 +3,7 @@ use std::{path::PathBuf, time::Duration};
 use anyhow::Context;
 use log::info;
 use rs4a_vapix::apis::firmware_management_1::{
-    AutoCommit, AutoRollback, FactoryDefaultMode, UpgradeRequest,
+    AutoCommit, AutoRollback, FactoryDefaultMode, UpgradeRequest, UploadProgress,
 };
 use tokio::time::timeout;
 
@@ -46,18 +46,21 @@ impl UpgradeCommand {
             .map(parse_auto_rollback)
             .transpose()?;
 
-        info!("Reading firmware from {:?}", self.firmware);
-        let firmware = tokio::fs::read(&self.firmware)
-            .await
-            .with_context(|| format!("Could not read firmware file {:?}", self.firmware))?;
-
         info!("Connecting to device");
         let client = self.netloc.connect().await?;
 
         let restart_detector = RestartDetector::try_new(&client).await?;
 
-        info!("Sending upgrade request");
-        let mut request = UpgradeRequest::new(firmware);
+        info!("Uploading firmware from {:?}", self.firmware);
+        let mut reported = 0;
+        let mut request =
+            UpgradeRequest::from_file(&self.firmware).on_progress(move |p: UploadProgress| {
+                let percent = p.sent.saturating_mul(100) / p.total.max(1);
+                if percent >= reported + 10 || p.sent == p.total {
+                    reported = percent;
+                    info!("Uploaded {percent}% ({} of {} bytes)", p.sent, p.total);
+                }
+            });
         if let Some(mode) = self.factory_default_mode {
             request = request.factory_default_mode(mode);
         }
@@ -4,10 +4,11 @@ mod ssh_keygen;
 
 use clap::{Parser, Subcommand};
 use rs4a_bin_utils::completions_command::CompletionsCommand;
+use rs4a_vapix::SchemePolicy;
 use url::Host;
 
 pub use crate::commands::{
-    init::{InitCommand, Profile},
+    init::{InitCommand, NetworkArgs, Profile},
     reinit::ReinitCommand,
     restore::RestoreCommand,
     upgrade::UpgradeCommand,
@@ -33,6 +34,9 @@ pub struct Netloc {
     /// Accept self-signed HTTPS certificates.
     #[arg(long, env = "AXIS_DEVICE_HTTPS_SELF_SIGNED", value_parser = clap::builder::BoolishValueParser::new())]
     pub https_self_signed: bool,
+    /// Which schemes to use, and when to fall back from HTTPS to HTTP.
+    #[arg(long, env = "AXIS_DEVICE_SCHEME_POLICY", value_enum, default_value_t = SchemePolicy::SecureOnly)]
+    pub scheme_policy: SchemePolicy,
 }
 
 impl Netloc {
@@ -45,6 +49,7 @@ impl Netloc {
             .plain_port(self.http_port)
             .secure_port(self.https_port)
             .username_password(user, &self.pass)
+            .scheme_policy(self.scheme_policy)
             .with_inner(|b| b.danger_accept_invalid_certs(self.https_self_signed))
             .build()
             .await
@@ -54,6 +59,7 @@ impl Netloc {
         rs4a_vapix::ClientBuilder::new(self.host.clone())
             .plain_port(self.http_port)
             .secure_port(self.https_port)
+            .scheme_policy(self.scheme_policy)
             .with_inner(|b| b.danger_accept_invalid_certs(self.https_self_signed))
             .build()
             .await
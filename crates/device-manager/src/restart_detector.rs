@@ -1,7 +1,10 @@
 use std::time::Duration;
 
 use log::{debug, trace, warn};
-use rs4a_vapix::apis::system_ready_1::{SystemReadyRequest, SystemreadyData};
+use rs4a_vapix::{
+    apis::system_ready_1::{SystemReadyRequest, SystemreadyData},
+    http::{RetryClient, RetryPolicy},
+};
 use tokio::time::sleep;
 
 #[derive(Clone, Copy, Debug)]
@@ -100,21 +103,20 @@ impl<'a> RestartDetector<'a> {
 
     pub(crate) async fn wait(self) {
         let mut state = DetectorState::WaitingToGoDown;
+        // The state machine polls repeatedly, so a failed request needs no retries of its own.
+        let client = RetryClient::new(
+            self.client,
+            RetryPolicy::never().timeout(Duration::from_secs(5)),
+        );
 
         while !matches!(state, DetectorState::Ready) {
             sleep(Duration::from_secs(1)).await;
 
-            let data = tokio::time::timeout(
-                Duration::from_secs(5),
-                SystemReadyRequest::new().send(self.client),
-            )
-            .await
-            .inspect_err(|_| debug!("system_ready request timed out"))
-            .ok()
-            .and_then(|r| {
-                r.inspect_err(|e| debug!("converting error to option: {e}"))
-                    .ok()
-            });
+            let data = SystemReadyRequest::new()
+                .send(&client)
+                .await
+                .inspect_err(|e| debug!("converting error to option: {e}"))
+                .ok();
 
             state = self.next_state(state, data);
         }
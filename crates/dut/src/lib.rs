@@ -19,7 +19,12 @@ pub struct Device {
     pub http_port: Option<u16>,
     pub https_port: Option<u16>,
     pub ssh_port: Option<u16>,
+    pub rtsp_port: Option<u16>,
     pub https_self_signed: bool,
+    /// The SHA-256 fingerprint of the HTTPS certificate that the device is expected to present.
+    pub https_fingerprint: Option<String>,
+    /// Which schemes clients may use; one of `secure-only`, `plain-only` or `prefer-secure`.
+    pub scheme_policy: Option<String>,
 }
 
 impl Device {
@@ -42,11 +47,17 @@ impl Device {
             .ok()
             .map(|p| p.parse())
             .transpose()?;
+        let rtsp_port = env::var("AXIS_DEVICE_RTSP_PORT")
+            .ok()
+            .map(|p| p.parse())
+            .transpose()?;
         let https_self_signed = env::var("AXIS_DEVICE_HTTPS_SELF_SIGNED")
             .ok()
             .map(|v| parse_boolish(&v))
             .transpose()?
             .unwrap_or(false);
+        let https_fingerprint = env::var("AXIS_DEVICE_HTTPS_FINGERPRINT").ok();
+        let scheme_policy = env::var("AXIS_DEVICE_SCHEME_POLICY").ok();
         Ok(Some(Self {
             host,
             username,
@@ -54,7 +65,10 @@ impl Device {
             http_port,
             https_port,
             ssh_port,
+            rtsp_port,
             https_self_signed,
+            https_fingerprint,
+            scheme_policy,
         }))
     }
 
@@ -66,7 +80,10 @@ impl Device {
             http_port,
             https_port,
             ssh_port,
+            rtsp_port,
             https_self_signed,
+            https_fingerprint,
+            scheme_policy,
         } = self;
         let mut envs = Vec::new();
 
@@ -94,11 +111,27 @@ impl Device {
             envs.push(("AXIS_DEVICE_HTTPS_PORT".to_string(), None));
         }
 
+        if let Some(p) = rtsp_port {
+            envs.push(("AXIS_DEVICE_RTSP_PORT".to_string(), Some(p.to_string())));
+        } else {
+            envs.push(("AXIS_DEVICE_RTSP_PORT".to_string(), None));
+        }
+
         envs.push((
             "AXIS_DEVICE_HTTPS_SELF_SIGNED".to_string(),
             Some(if *https_self_signed { "1" } else { "0" }.to_string()),
         ));
 
+        envs.push((
+            "AXIS_DEVICE_HTTPS_FINGERPRINT".to_string(),
+            https_fingerprint.clone(),
+        ));
+
+        envs.push((
+            "AXIS_DEVICE_SCHEME_POLICY".to_string(),
+            scheme_policy.clone(),
+        ));
+
         envs
     }
 
@@ -1,13 +1,19 @@
 //! A gallery of bindings for various APIs
 
 mod axis_cgi;
+mod axis_media;
 mod config;
 mod services;
+mod vapix;
 pub use axis_cgi::{
-    api_discovery_1, applications_config, basic_device_info_1, firmware_management_1, jpg_3,
-    network_settings_1, parameter_management, pwdgrp, system_ready_1,
+    api_discovery_1, applications, applications_config, basic_device_info_1, firmware_management_1,
+    jpg_3, network_settings_1, ntp_1, parameter_management, pwdgrp, server_report, system_log,
+    system_ready_1, time_1,
 };
+pub use axis_media::rtsp;
 pub use config::{
     discover, recording_group_1, remote_object_storage_1_beta, siren_and_light_2_alpha, ssh_1,
+    ssh_2,
 };
-pub use services::{action1, event1};
+pub use services::{action1, cert, event1};
+pub use vapix::event_stream_1;
@@ -1,10 +1,15 @@
 //! A collection of APIs that can be found under the `/axis-cgi/` path.
 pub mod api_discovery_1;
+pub mod applications;
 pub mod applications_config;
 pub mod basic_device_info_1;
 pub mod firmware_management_1;
 pub mod jpg_3;
 pub mod network_settings_1;
+pub mod ntp_1;
 pub mod parameter_management;
 pub mod pwdgrp;
+pub mod server_report;
+pub mod system_log;
 pub mod system_ready_1;
+pub mod time_1;
@@ -0,0 +1,484 @@
+//! The [Application API] for installing and controlling ACAP applications.
+//!
+//! [Application API]: https://developer.axis.com/vapix/applications/application-api/
+
+use std::{
+    fmt::{Display, Formatter},
+    path::PathBuf,
+};
+
+use anyhow::Context;
+use reqwest::{Method, StatusCode};
+use serde::{Deserialize, Serialize};
+use tokio::io::AsyncRead;
+
+use crate::{
+    http::{HttpClient, Request, Response, Upload},
+    protocol_helpers::http::{encode_query_value, Error as HttpError},
+};
+
+const UPLOAD_PATH: &str = "axis-cgi/applications/upload.cgi";
+
+const CONTROL_PATH: &str = "axis-cgi/applications/control.cgi";
+
+const LIST_PATH: &str = "axis-cgi/applications/list.cgi";
+
+const LICENSE_PATH: &str = "axis-cgi/applications/license.cgi";
+
+const BOUNDARY: &str = "----FormBoundaryq2bQ8ZHy9o4qNsCf";
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ErrorKind {
+    /// The package could not be verified or is not a valid application package.
+    InvalidPackage = 1,
+    /// The package is too large or there is not enough space on the device.
+    InsufficientSpace = 2,
+    /// The package is not compatible with the device or its firmware.
+    Incompatible = 3,
+    NotFound = 4,
+    AlreadyRunning = 6,
+    NotRunning = 7,
+    CouldNotStart = 8,
+    TooManyRunning = 9,
+    Other = 10,
+}
+
+impl TryFrom<u16> for ErrorKind {
+    type Error = u16;
+
+    fn try_from(code: u16) -> Result<Self, u16> {
+        match code {
+            1 => Ok(Self::InvalidPackage),
+            2 => Ok(Self::InsufficientSpace),
+            3 => Ok(Self::Incompatible),
+            4 => Ok(Self::NotFound),
+            6 => Ok(Self::AlreadyRunning),
+            7 => Ok(Self::NotRunning),
+            8 => Ok(Self::CouldNotStart),
+            9 => Ok(Self::TooManyRunning),
+            10 => Ok(Self::Other),
+            _ => Err(code),
+        }
+    }
+}
+
+/// An error returned by the application CGIs, e.g. `Error: 4`.
+#[derive(Clone, Debug)]
+pub struct Error {
+    pub code: u16,
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Error: {}", self.code)
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Parse the plain text reply shared by the upload, control and license CGIs.
+fn parse_ok_reply(response: Response) -> Result<(), HttpError<Error>> {
+    let Response { status, body } = response;
+    let body = body
+        .with_context(|| format!("Could not fetch text, status was {status}"))
+        .map_err(HttpError::Transport)?;
+    let trimmed = body.trim();
+    if let Some(code) = trimmed.strip_prefix("Error:") {
+        let code = code
+            .trim()
+            .parse()
+            .with_context(|| format!("Could not parse error code; text: {trimmed}"))
+            .map_err(HttpError::Decode)?;
+        return Err(HttpError::Service(Error { code }));
+    }
+    if status == StatusCode::OK && trimmed == "OK" {
+        return Ok(());
+    }
+    Err(HttpError::Decode(anyhow::anyhow!(
+        "Unexpected response: {status} {trimmed}"
+    )))
+}
+
+/// Escape `filename` for use in a quoted `Content-Disposition` parameter, the way browsers do.
+fn escape_filename(filename: &str) -> String {
+    filename
+        .replace('"', "%22")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+fn multipart_head(name: &str, filename: &str) -> Vec<u8> {
+    let filename = escape_filename(filename);
+    let mut head = Vec::new();
+    head.extend_from_slice(format!("--{BOUNDARY}\r\n").as_bytes());
+    head.extend_from_slice(
+        format!("Content-Disposition: form-data; name=\"{name}\"; filename=\"{filename}\"\r\n")
+            .as_bytes(),
+    );
+    head.extend_from_slice(b"Content-Type: application/octet-stream\r\n\r\n");
+    head
+}
+
+fn multipart_tail() -> Vec<u8> {
+    format!("\r\n--{BOUNDARY}--\r\n").into_bytes()
+}
+
+fn build_multipart_body(name: &str, filename: &str, content: &[u8]) -> Vec<u8> {
+    let mut body = multipart_head(name, filename);
+    body.extend_from_slice(content);
+    body.extend_from_slice(&multipart_tail());
+    body
+}
+
+/// Install an application from an `.eap` package.
+///
+/// Installing an application that is already installed upgrades it.
+pub struct UploadRequest {
+    filename: String,
+    package: Upload,
+}
+
+impl UploadRequest {
+    /// Install a package that is already in memory.
+    ///
+    /// Prefer [`Self::from_file`] or [`Self::from_reader`] for large packages; they stream the
+    /// package instead of copying it into the request body.
+    pub fn new(filename: &str, package: Vec<u8>) -> Self {
+        Self {
+            filename: filename.to_string(),
+            package: Upload::Bytes(package),
+        }
+    }
+
+    /// Install the package at `path`.
+    ///
+    /// The file is not opened until the request is sent.
+    pub fn from_file(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        Self {
+            filename: path
+                .file_name()
+                .map(|f| f.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+            package: Upload::File(path),
+        }
+    }
+
+    /// Install a package of `len` bytes read from `reader`.
+    pub fn from_reader(filename: &str, reader: impl AsyncRead + Send + 'static, len: u64) -> Self {
+        Self {
+            filename: filename.to_string(),
+            package: Upload::reader(reader, len),
+        }
+    }
+
+    async fn into_request(self) -> anyhow::Result<Request> {
+        let Self { filename, package } = self;
+        package
+            .into_multipart(
+                Request::new(Method::POST, UPLOAD_PATH.to_string()),
+                BOUNDARY,
+                multipart_head("packfil", &filename),
+                multipart_tail(),
+                None,
+            )
+            .await
+    }
+
+    pub async fn send(self, client: &(impl HttpClient + Sync)) -> Result<(), HttpError<Error>> {
+        let request = self.into_request().await.map_err(HttpError::Request)?;
+        let response = client
+            .execute(request)
+            .await
+            .map_err(HttpError::Transport)?;
+        parse_ok_reply(response)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Action {
+    Start,
+    Stop,
+    Restart,
+    Remove,
+}
+
+impl Display for Action {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Start => write!(f, "start"),
+            Self::Stop => write!(f, "stop"),
+            Self::Restart => write!(f, "restart"),
+            Self::Remove => write!(f, "remove"),
+        }
+    }
+}
+
+/// Start, stop, restart or remove an installed application.
+#[derive(Clone, Debug)]
+pub struct ControlRequest {
+    action: Action,
+    package: String,
+}
+
+impl ControlRequest {
+    /// Perform `action` on the application called `package`, e.g. `"vdoencodeclient"`.
+    pub fn new(action: Action, package: &str) -> Self {
+        Self {
+            action,
+            package: package.to_string(),
+        }
+    }
+
+    pub fn start(package: &str) -> Self {
+        Self::new(Action::Start, package)
+    }
+
+    pub fn stop(package: &str) -> Self {
+        Self::new(Action::Stop, package)
+    }
+
+    pub fn restart(package: &str) -> Self {
+        Self::new(Action::Restart, package)
+    }
+
+    pub fn remove(package: &str) -> Self {
+        Self::new(Action::Remove, package)
+    }
+
+    pub fn into_request(self) -> Request {
+        let Self { action, package } = self;
+        Request::new(
+            Method::GET,
+            format!(
+                "{CONTROL_PATH}?action={action}&package={}",
+                encode_query_value(&package)
+            ),
+        )
+    }
+
+    pub async fn send(self, client: &(impl HttpClient + Sync)) -> Result<(), HttpError<Error>> {
+        let response = client
+            .execute(self.into_request())
+            .await
+            .map_err(HttpError::Transport)?;
+        parse_ok_reply(response)
+    }
+}
+
+/// Install a license key for an application.
+pub struct UploadLicenseRequest {
+    package: String,
+    key: Vec<u8>,
+}
+
+impl UploadLicenseRequest {
+    pub fn new(package: &str, key: Vec<u8>) -> Self {
+        Self {
+            package: package.to_string(),
+            key,
+        }
+    }
+
+    pub fn into_request(self) -> Request {
+        let Self { package, key } = self;
+        let body = build_multipart_body("fileName", "license.xml", &key);
+        Request::new(
+            Method::POST,
+            format!(
+                "{LICENSE_PATH}?action=uploadlicensekey&package={}",
+                encode_query_value(&package)
+            ),
+        )
+        .multipart(body, BOUNDARY)
+    }
+
+    pub async fn send(self, client: &(impl HttpClient + Sync)) -> Result<(), HttpError<Error>> {
+        let response = client
+            .execute(self.into_request())
+            .await
+            .map_err(HttpError::Transport)?;
+        parse_ok_reply(response)
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum Status {
+    Running,
+    Stopped,
+    Idle,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Application {
+    #[serde(rename = "@Name")]
+    pub name: String,
+    #[serde(rename = "@NiceName")]
+    pub nice_name: String,
+    #[serde(rename = "@Vendor")]
+    pub vendor: String,
+    #[serde(rename = "@Version")]
+    pub version: String,
+    #[serde(rename = "@ApplicationID", default)]
+    pub application_id: Option<String>,
+    #[serde(rename = "@License", default)]
+    pub license: Option<String>,
+    #[serde(rename = "@Status")]
+    pub status: Status,
+    #[serde(rename = "@ConfigurationPage", default)]
+    pub configuration_page: Option<String>,
+    #[serde(rename = "@VendorHomePage", default)]
+    pub vendor_home_page: Option<String>,
+    #[serde(rename = "@LicenseName", default)]
+    pub license_name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Reply {
+    #[serde(rename = "@result")]
+    result: String,
+    #[serde(rename = "application", default)]
+    applications: Vec<Application>,
+}
+
+#[derive(Clone, Debug)]
+pub struct ListData {
+    pub applications: Vec<Application>,
+}
+
+impl ListData {
+    pub fn find(&self, name: &str) -> Option<&Application> {
+        self.applications.iter().find(|a| a.name == name)
+    }
+}
+
+fn parse_list_reply(text: &str) -> anyhow::Result<ListData> {
+    let Reply {
+        result,
+        applications,
+    } = quick_xml::de::from_str(text)
+        .with_context(|| format!("Could not parse reply; text: {text}"))?;
+    anyhow::ensure!(result == "ok", "Unexpected result {result}; text: {text}");
+    Ok(ListData { applications })
+}
+
+/// List the installed applications.
+#[derive(Clone, Debug, Default)]
+pub struct ListRequest;
+
+impl ListRequest {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn into_request(self) -> Request {
+        Request::new(Method::GET, LIST_PATH.to_string())
+    }
+
+    pub async fn send(
+        self,
+        client: &(impl HttpClient + Sync),
+    ) -> Result<ListData, HttpError<std::convert::Infallible>> {
+        let Response { status, body } = client
+            .execute(self.into_request())
+            .await
+            .map_err(HttpError::Transport)?;
+        let body = body
+            .with_context(|| format!("Could not fetch text, status was {status}"))
+            .map_err(HttpError::Transport)?;
+        parse_list_reply(&body).map_err(HttpError::Decode)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use futures_util::StreamExt;
+
+    use super::*;
+    use crate::http::Body;
+
+    fn response(status: StatusCode, body: &str) -> Response {
+        Response {
+            status,
+            body: Ok(body.to_string()),
+        }
+    }
+
+    #[test]
+    fn can_parse_list_reply() {
+        let text = include_str!("applications/list_200.xml");
+        let data = parse_list_reply(text).unwrap();
+        assert_eq!(data.applications.len(), 2);
+        let app = data.find("vdoencodeclient").unwrap();
+        assert_eq!(app.status, Status::Running);
+        assert_eq!(app.version, "1.0.0");
+        assert_eq!(app.license, Some("None".to_string()));
+        assert_eq!(data.find("hello_world").unwrap().status, Status::Stopped);
+    }
+
+    #[test]
+    fn can_parse_empty_list_reply() {
+        let data = parse_list_reply(r#"<reply result="ok"></reply>"#).unwrap();
+        assert!(data.applications.is_empty());
+    }
+
+    #[test]
+    fn can_parse_ok_and_error_replies() {
+        parse_ok_reply(response(StatusCode::OK, "OK\r\n")).unwrap();
+        let error = parse_ok_reply(response(StatusCode::OK, "Error: 4\r\n"))
+            .unwrap_err()
+            .unwrap_service();
+        assert_eq!(ErrorKind::try_from(error.code), Ok(ErrorKind::NotFound));
+        assert!(matches!(
+            parse_ok_reply(response(StatusCode::OK, "<html></html>")),
+            Err(HttpError::Decode(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn streamed_upload_matches_buffered_upload() {
+        let package = b"not really a package".to_vec();
+        let Request {
+            body: Some(Body::Bytes(expected)),
+            ..
+        } = UploadRequest::new("app.eap", package.clone())
+            .into_request()
+            .await
+            .unwrap()
+        else {
+            panic!("Expected a buffered body");
+        };
+
+        let len = u64::try_from(package.len()).unwrap();
+        let Request {
+            body: Some(Body::Stream(body)),
+            ..
+        } = UploadRequest::from_reader("app.eap", io::Cursor::new(package), len)
+            .into_request()
+            .await
+            .unwrap()
+        else {
+            panic!("Expected a streamed body");
+        };
+        assert_eq!(body.len(), u64::try_from(expected.len()).unwrap());
+        let actual: Vec<u8> = body.into_stream().map(Result::unwrap).concat().await;
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn multipart_filename_is_escaped() {
+        let head = String::from_utf8(multipart_head("packfil", "a\"b\r\nc.eap")).unwrap();
+        assert!(head.contains(r#"filename="a%22b%0D%0Ac.eap""#));
+    }
+
+    #[test]
+    fn control_request_encodes_package() {
+        let request = ControlRequest::stop("my app").into_request();
+        assert_eq!(
+            request.path,
+            "axis-cgi/applications/control.cgi?action=stop&package=my+app"
+        );
+    }
+}
@@ -2,11 +2,15 @@
 //!
 //! [Configure Applications]: https://developer.axis.com/vapix/applications/application-api/#configure-applications
 
+use std::fmt::{Display, Formatter};
+
+use anyhow::Context;
 use reqwest::{Method, StatusCode};
+use serde::{Deserialize, Serialize};
 
 use crate::{
     http::{HttpClient, Request},
-    protocol_helpers::http::Error,
+    protocol_helpers::http::{encode_query_value, Error},
 };
 
 const PATH: &str = "axis-cgi/applications/config.cgi";
@@ -18,9 +22,16 @@ fn bool2string(b: bool) -> &'static str {
     }
 }
 
+#[derive(Clone, Copy, Debug)]
+enum Action {
+    Get,
+    Set,
+}
+
 #[derive(Clone, Debug)]
 pub struct ApplicationConfigRequest {
-    name: &'static str,
+    action: Action,
+    name: String,
     value: Option<&'static str>,
 }
 
@@ -32,7 +43,8 @@ impl ApplicationConfigRequest {
     /// - `false` since AXIS OS 11.8
     pub fn allow_root(allow: bool) -> Self {
         Self {
-            name: "AllowRoot",
+            action: Action::Set,
+            name: "AllowRoot".to_string(),
             value: Some(bool2string(allow)),
         }
     }
@@ -44,40 +56,182 @@ impl ApplicationConfigRequest {
     /// - `false` since AXIS OS 12.0
     pub fn allow_unsigned(allow: bool) -> Self {
         Self {
-            name: "AllowUnsigned",
+            action: Action::Set,
+            name: "AllowUnsigned".to_string(),
             value: Some(bool2string(allow)),
         }
     }
 
+    /// Get the current value of the setting called `name`, e.g. `"AllowUnsigned"`.
+    ///
+    /// Unknown names are reported as a [`ReplyError`].
+    pub fn get(name: &str) -> Self {
+        Self {
+            action: Action::Get,
+            name: name.to_string(),
+            value: None,
+        }
+    }
+
     fn into_request(self) -> Request {
-        let Self { name, value } = self;
-        let path = match value {
-            None => format!("{PATH}?action=set&name={name}"),
-            Some(value) => format!("{PATH}?action=set&name={name}&value={value}"),
+        let Self {
+            action,
+            name,
+            value,
+        } = self;
+        let name = encode_query_value(&name);
+        let path = match (action, value) {
+            (Action::Get, _) => format!("{PATH}?action=get&name={name}"),
+            (Action::Set, None) => format!("{PATH}?action=set&name={name}"),
+            (Action::Set, Some(value)) => format!("{PATH}?action=set&name={name}&value={value}"),
         };
         Request::new(Method::GET, path)
     }
 
-    // TODO: Implement lossless self-checks
-    // Get requests return XML that needs parsing
-    // TODO: Add support for get requests
     pub async fn send(
         self,
         client: &impl HttpClient,
-    ) -> Result<(), Error<std::convert::Infallible>> {
+    ) -> Result<ApplicationConfigData, Error<ReplyError>> {
         let response = client
             .execute(self.into_request())
             .await
             .map_err(Error::Transport)?;
         let body = response.body.map_err(|e| Error::Transport(e.into()))?;
-        if response.status == StatusCode::OK && body.trim().starts_with(r#"<reply result="ok">"#) {
-            Ok(())
-        } else {
-            Err(Error::Decode(anyhow::anyhow!(
+        if response.status != StatusCode::OK {
+            return Err(Error::Decode(anyhow::anyhow!(
                 "Unexpected response: {} {}",
                 response.status,
                 body.trim()
-            )))
+            )));
         }
+        Error::flat_result(parse_reply(&body))
+    }
+}
+
+/// An error reported in the reply document, e.g. when the setting does not exist.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ReplyError {
+    #[serde(rename = "@type")]
+    pub code: u16,
+    #[serde(rename = "@message", default)]
+    pub message: String,
+}
+
+impl Display for ReplyError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let Self { code, message } = self;
+        write!(f, "({code}) {message}")
+    }
+}
+
+impl std::error::Error for ReplyError {}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Param {
+    #[serde(rename = "@name")]
+    pub name: String,
+    #[serde(rename = "@value")]
+    pub value: String,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum ReplyResult {
+    Ok,
+    Error,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct Reply {
+    #[serde(rename = "@result")]
+    result: ReplyResult,
+    #[serde(rename = "param", default)]
+    params: Vec<Param>,
+    #[serde(default)]
+    error: Option<ReplyError>,
+}
+
+#[derive(Clone, Debug)]
+pub struct ApplicationConfigData {
+    /// The settings included in the reply; empty for most set requests.
+    pub params: Vec<Param>,
+}
+
+impl ApplicationConfigData {
+    pub fn value(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|p| p.name == name)
+            .map(|p| p.value.as_str())
+    }
+
+    /// Like [`Self::value`] but for settings that are booleans, like `AllowUnsigned`.
+    pub fn bool_value(&self, name: &str) -> anyhow::Result<Option<bool>> {
+        self.value(name)
+            .map(|v| match v {
+                "true" => Ok(true),
+                "false" => Ok(false),
+                _ => Err(anyhow::anyhow!("Expected true or false but got {v}")),
+            })
+            .transpose()
+    }
+}
+
+fn parse_reply(text: &str) -> anyhow::Result<Result<ApplicationConfigData, ReplyError>> {
+    let Reply {
+        result,
+        params,
+        error,
+    } = quick_xml::de::from_str(text)
+        .with_context(|| format!("Could not parse reply; text: {text}"))?;
+    match (result, error) {
+        (ReplyResult::Ok, None) => Ok(Ok(ApplicationConfigData { params })),
+        (ReplyResult::Error, Some(error)) if params.is_empty() => Ok(Err(error)),
+        _ => anyhow::bail!("Reply is inconsistent; text: {text}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_parse_get_reply() {
+        let text = include_str!("applications_config/get_200.xml");
+        let data = parse_reply(text).unwrap().unwrap();
+        assert_eq!(data.value("AllowUnsigned"), Some("true"));
+        assert_eq!(data.bool_value("AllowUnsigned").unwrap(), Some(true));
+        assert_eq!(data.bool_value("AllowRoot").unwrap(), None);
+    }
+
+    #[test]
+    fn can_parse_set_reply() {
+        let data = parse_reply(r#"<reply result="ok"></reply>"#)
+            .unwrap()
+            .unwrap();
+        assert!(data.params.is_empty());
+    }
+
+    #[test]
+    fn can_parse_error_reply() {
+        let text = include_str!("applications_config/get_200_error.xml");
+        let error = parse_reply(text).unwrap().unwrap_err();
+        assert_eq!(error.code, 1);
+    }
+
+    #[test]
+    fn ignores_unknown_content() {
+        let text = r#"<reply result="ok" extra="1"><param name="AllowRoot" value="true" extra="1"/><extra/></reply>"#;
+        let data = parse_reply(text).unwrap().unwrap();
+        assert_eq!(data.value("AllowRoot"), Some("true"));
+    }
+
+    #[test]
+    fn get_request_encodes_name() {
+        let request = ApplicationConfigRequest::get("Allow Unsigned").into_request();
+        assert_eq!(
+            request.path,
+            "axis-cgi/applications/config.cgi?action=get&name=Allow+Unsigned"
+        );
     }
 }
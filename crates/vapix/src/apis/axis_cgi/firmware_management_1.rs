@@ -2,14 +2,18 @@
 //!
 //! [Firmware Management API]: https://developer.axis.com/vapix/network-video/firmware-management-api/
 
-use std::fmt::{Display, Formatter};
+use std::{
+    fmt::{Display, Formatter},
+    path::PathBuf,
+};
 
 use anyhow::Context;
 use reqwest::Method;
 use serde::{Deserialize, Serialize};
+use tokio::io::AsyncRead;
 
 use crate::{
-    http::{HttpClient, Request},
+    http::{HttpClient, Request, Upload},
     protocol_helpers::{http::Error, json_rpc, json_rpc_http},
 };
 
@@ -120,6 +124,149 @@ impl Default for FactoryDefaultRequest {
     }
 }
 
+/// Body of the requests that take no parameters.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct NoParams {
+    api_version: &'static str,
+    method: &'static str,
+}
+
+impl NoParams {
+    fn new(method: &'static str) -> Self {
+        Self {
+            api_version: "1.0",
+            method,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatusData {
+    pub active_firmware_version: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub active_firmware_part: Option<String>,
+    /// The firmware that a rollback would restore, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub inactive_firmware_version: Option<String>,
+    /// Spelled `isCommited` in the API, but the correct spelling is accepted too.
+    #[serde(rename = "isCommited", alias = "isCommitted")]
+    pub is_committed: bool,
+    /// Seconds until the active firmware is rolled back unless it is committed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time_to_rollback: Option<u32>,
+    /// When the last upgrade was performed, in RFC 3339 format.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_upgrade_at: Option<String>,
+}
+
+/// Get the firmware status, including whether the active firmware is committed.
+#[derive(Debug, Default)]
+pub struct StatusRequest;
+
+impl StatusRequest {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub async fn send(
+        self,
+        client: &(impl HttpClient + Sync),
+    ) -> Result<StatusData, Error<json_rpc::Error>> {
+        json_rpc_http::send_request(client, PATH, &NoParams::new("status")).await
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CommitData {}
+
+/// Commit the active firmware, cancelling any pending automatic rollback.
+#[derive(Debug, Default)]
+pub struct CommitRequest;
+
+impl CommitRequest {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub async fn send(
+        self,
+        client: &(impl HttpClient + Sync),
+    ) -> Result<CommitData, Error<json_rpc::Error>> {
+        json_rpc_http::send_request(client, PATH, &NoParams::new("commit")).await
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RollbackData {
+    /// The firmware that will be active after the device restarts.
+    pub firmware_version: String,
+}
+
+/// Roll back to the inactive firmware.
+///
+/// Fails with [`ErrorKind::NoPreviousFirmware`] if there is no inactive firmware.
+/// The device restarts after responding.
+#[derive(Debug, Default)]
+pub struct RollbackRequest;
+
+impl RollbackRequest {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub async fn send(
+        self,
+        client: &(impl HttpClient + Sync),
+    ) -> Result<RollbackData, Error<json_rpc::Error>> {
+        json_rpc_http::send_request(client, PATH, &NoParams::new("rollback")).await
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct PurgeData {}
+
+/// Remove the inactive firmware, making it impossible to roll back.
+///
+/// Fails with [`ErrorKind::UncommittedFirmware`] if the active firmware is not committed.
+#[derive(Debug, Default)]
+pub struct PurgeRequest;
+
+impl PurgeRequest {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub async fn send(
+        self,
+        client: &(impl HttpClient + Sync),
+    ) -> Result<PurgeData, Error<json_rpc::Error>> {
+        json_rpc_http::send_request(client, PATH, &NoParams::new("purge")).await
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct RebootData {}
+
+/// Restart the device.
+#[derive(Debug, Default)]
+pub struct RebootRequest;
+
+impl RebootRequest {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub async fn send(
+        self,
+        client: &(impl HttpClient + Sync),
+    ) -> Result<RebootData, Error<json_rpc::Error>> {
+        json_rpc_http::send_request(client, PATH, &NoParams::new("reboot")).await
+    }
+}
+
 #[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
 #[derive(Clone, Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -179,13 +326,47 @@ pub struct UpgradeRequestJson {
     params: UpgradeParams,
 }
 
+/// How far an upload has progressed.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct UploadProgress {
+    /// Number of firmware bytes handed to the transport so far.
+    pub sent: u64,
+    /// Size of the firmware in bytes.
+    pub total: u64,
+}
+
+type ProgressCallback = Box<dyn FnMut(UploadProgress) + Send>;
+
+const BOUNDARY: &str = "----FormBoundaryS6untlhO8j7poXo";
+
 pub struct UpgradeRequest {
     json: UpgradeRequestJson,
-    bin: Vec<u8>,
+    firmware: Upload,
+    progress: Option<ProgressCallback>,
 }
 
 impl UpgradeRequest {
+    /// Upgrade using a firmware image that is already in memory.
+    ///
+    /// Prefer [`Self::from_file`] or [`Self::from_reader`] for large images; they stream the
+    /// image instead of copying it into the request body.
     pub fn new(bin: Vec<u8>) -> Self {
+        Self::with_firmware(Upload::Bytes(bin))
+    }
+
+    /// Upgrade using the firmware image at `path`.
+    ///
+    /// The file is not opened until the request is sent.
+    pub fn from_file(path: impl Into<PathBuf>) -> Self {
+        Self::with_firmware(Upload::File(path.into()))
+    }
+
+    /// Upgrade using a firmware image of `len` bytes read from `reader`.
+    pub fn from_reader(reader: impl AsyncRead + Send + 'static, len: u64) -> Self {
+        Self::with_firmware(Upload::reader(reader, len))
+    }
+
+    fn with_firmware(firmware: Upload) -> Self {
         Self {
             json: UpgradeRequestJson {
                 api_version: "1.0",
@@ -196,7 +377,8 @@ impl UpgradeRequest {
                     auto_rollback: None,
                 },
             },
-            bin,
+            firmware,
+            progress: None,
         }
     }
 
@@ -215,42 +397,64 @@ impl UpgradeRequest {
         self
     }
 
-    fn build_multipart_body(json: &[u8], firmware: &[u8], boundary: &str) -> Vec<u8> {
-        let mut body = Vec::new();
+    /// Call `callback` as the firmware is uploaded.
+    ///
+    /// Progress is reported only for streamed images, i.e. not when created with [`Self::new`].
+    pub fn on_progress(mut self, callback: impl FnMut(UploadProgress) + Send + 'static) -> Self {
+        self.progress = Some(Box::new(callback));
+        self
+    }
+
+    fn multipart_head(json: &[u8]) -> Vec<u8> {
+        let mut head = Vec::new();
+
+        head.extend_from_slice(format!("--{BOUNDARY}\r\n").as_bytes());
 
-        body.extend_from_slice(format!("--{boundary}\r\n").as_bytes());
+        head.extend_from_slice(b"Content-Disposition: form-data; name=\"data\"\r\n");
+        head.extend_from_slice(b"Content-Type: application/json\r\n\r\n");
+        head.extend_from_slice(json);
+        head.extend_from_slice(b"\r\n");
 
-        body.extend_from_slice(b"Content-Disposition: form-data; name=\"data\"\r\n");
-        body.extend_from_slice(b"Content-Type: application/json\r\n\r\n");
-        body.extend_from_slice(json);
-        body.extend_from_slice(b"\r\n");
+        head.extend_from_slice(format!("--{BOUNDARY}\r\n").as_bytes());
 
-        body.extend_from_slice(format!("--{boundary}\r\n").as_bytes());
+        head.extend_from_slice(b"Content-Disposition: form-data; name=\"firmwareImage\"; filename=\"firmware.bin\"\r\n");
+        head.extend_from_slice(b"Content-Type: application/octet-stream\r\n\r\n");
 
-        body.extend_from_slice(b"Content-Disposition: form-data; name=\"firmwareImage\"; filename=\"firmware.bin\"\r\n");
-        body.extend_from_slice(b"Content-Type: application/octet-stream\r\n\r\n");
-        body.extend_from_slice(firmware);
-        body.extend_from_slice(b"\r\n");
+        head
+    }
 
-        body.extend_from_slice(format!("--{boundary}--\r\n").as_bytes());
+    fn multipart_tail() -> Vec<u8> {
+        format!("\r\n--{BOUNDARY}--\r\n").into_bytes()
+    }
 
-        body
+    async fn into_request(self) -> anyhow::Result<Request> {
+        let Self {
+            json,
+            firmware,
+            progress,
+        } = self;
+
+        let json = serde_json::to_string(&json).context("serialize request failed")?;
+        let progress = progress.map(|mut progress| -> crate::http::ProgressCallback {
+            Box::new(move |sent, total| progress(UploadProgress { sent, total }))
+        });
+
+        firmware
+            .into_multipart(
+                Request::new(Method::POST, PATH.to_string()),
+                BOUNDARY,
+                Self::multipart_head(json.as_bytes()),
+                Self::multipart_tail(),
+                progress,
+            )
+            .await
     }
 
     pub async fn send(
         self,
         client: &(impl HttpClient + Sync),
     ) -> Result<UpgradeData, Error<json_rpc::Error>> {
-        let boundary = "----FormBoundaryS6untlhO8j7poXo";
-
-        let json = serde_json::to_string(&self.json)
-            .context("serialize request failed")
-            .map_err(Error::Request)?;
-
-        let body = Self::build_multipart_body(json.as_bytes(), &self.bin, boundary);
-
-        let request = Request::new(Method::POST, PATH.to_string()).multipart(body, boundary);
-
+        let request = self.into_request().await.map_err(Error::Request)?;
         let response = client.execute(request).await.map_err(Error::Transport)?;
 
         json_rpc_http::from_response(response.status, response.body)
@@ -259,9 +463,16 @@ impl UpgradeRequest {
 
 #[cfg(test)]
 mod tests {
+    use std::{
+        io,
+        sync::{Arc, Mutex},
+    };
+
     use expect_test::expect;
+    use futures_util::StreamExt;
 
     use super::*;
+    use crate::http::Body;
 
     #[test]
     fn upgrade_request_json_envelope() {
@@ -283,6 +494,48 @@ mod tests {
         .assert_eq(&json);
     }
 
+    #[tokio::test]
+    async fn streamed_upgrade_matches_buffered_upgrade() {
+        let firmware = b"not really firmware".to_vec();
+        let Request {
+            body: Some(Body::Bytes(expected)),
+            ..
+        } = UpgradeRequest::new(firmware.clone())
+            .into_request()
+            .await
+            .unwrap()
+        else {
+            panic!("Expected a buffered body");
+        };
+
+        let len = u64::try_from(firmware.len()).unwrap();
+        let reports = Arc::new(Mutex::new(Vec::new()));
+        let Request {
+            body: Some(Body::Stream(body)),
+            ..
+        } = UpgradeRequest::from_reader(io::Cursor::new(firmware), len)
+            .on_progress({
+                let reports = Arc::clone(&reports);
+                move |p| reports.lock().unwrap().push(p)
+            })
+            .into_request()
+            .await
+            .unwrap()
+        else {
+            panic!("Expected a streamed body");
+        };
+        assert_eq!(body.len(), u64::try_from(expected.len()).unwrap());
+        let actual: Vec<u8> = body.into_stream().map(Result::unwrap).concat().await;
+        assert_eq!(actual, expected);
+        assert_eq!(
+            *reports.lock().unwrap(),
+            [UploadProgress {
+                sent: len,
+                total: len
+            }]
+        );
+    }
+
     #[test]
     fn upgrade_request_minimal() {
         let request = UpgradeRequest::new(Vec::new());
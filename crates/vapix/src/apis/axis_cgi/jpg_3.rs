@@ -1,30 +1,102 @@
-//! Bindings for the [JPEG image snapsnot API](https://developer.axis.com/vapix/network-video/video-streaming/#jpeg-image-snapshot).
+//! Bindings for the [JPEG image snapsnot API](https://developer.axis.com/vapix/network-video/video-streaming/#jpeg-image-snapshot)
+//! and the MJPEG video stream that shares its options.
 use anyhow::{bail, Context};
+use reqwest::header::CONTENT_TYPE;
 
-use crate::Client;
+use crate::{apis::parameter_management::Resolution, Client};
+
+const IMAGE_PATH: &str = "axis-cgi/jpg/image.cgi";
+
+const VIDEO_PATH: &str = "axis-cgi/mjpg/video.cgi";
+
+const JPEG_MAGIC: &[u8] = b"\xFF\xD8\xFF";
+
+fn bool_param(value: bool) -> String {
+    match value {
+        true => "1".to_string(),
+        false => "0".to_string(),
+    }
+}
+
+/// Options shared by snapshots and MJPEG streams.
+#[derive(Clone, Debug, Default)]
+struct ImageOptions {
+    resolution: Option<Resolution>,
+    camera: Option<u8>,
+    compression: Option<u8>,
+    rotation: Option<u16>,
+    text: Option<bool>,
+    date: Option<bool>,
+    clock: Option<bool>,
+    overlay_image: Option<bool>,
+}
+
+impl ImageOptions {
+    fn query(&self) -> Vec<(&'static str, String)> {
+        let Self {
+            resolution,
+            camera,
+            compression,
+            rotation,
+            text,
+            date,
+            clock,
+            overlay_image,
+        } = self;
+        let mut query = Vec::new();
+        if let Some(resolution) = resolution {
+            query.push(("resolution", resolution.to_string()));
+        }
+        if let Some(camera) = camera {
+            query.push(("camera", camera.to_string()));
+        }
+        if let Some(compression) = compression {
+            query.push(("compression", compression.to_string()));
+        }
+        if let Some(rotation) = rotation {
+            query.push(("rotation", rotation.to_string()));
+        }
+        if let Some(text) = text {
+            query.push(("text", bool_param(*text)));
+        }
+        if let Some(date) = date {
+            query.push(("date", bool_param(*date)));
+        }
+        if let Some(clock) = clock {
+            query.push(("clock", bool_param(*clock)));
+        }
+        if let Some(overlay_image) = overlay_image {
+            query.push(("overlayimage", bool_param(*overlay_image)));
+        }
+        query
+    }
+}
 
 /// Get a jpg encoded snapshot.
+#[derive(Clone, Debug, Default)]
 pub struct GetImageRequest {
-    resolution: Option<String>,
-    compression: Option<u8>,
+    options: ImageOptions,
 }
 
 impl GetImageRequest {
     pub fn new() -> Self {
-        Self {
-            resolution: None,
-            compression: None,
-        }
+        Self::default()
     }
 
-    /// The image resolution in the format `"{width}x{height}"`.
+    /// The image resolution.
     ///
-    /// Example: `"640x360"`.
+    /// Valid values are product-dependent and can be listed using
+    /// [`ImageResolution`](crate::apis::parameter_management::ImageResolution).
+    pub fn resolution(mut self, resolution: Resolution) -> Self {
+        self.options.resolution = Some(resolution);
+        self
+    }
+
+    /// The video channel, starting from `1`.
     ///
-    /// Valid values are product-dependent.
-    // TODO: Document how to retrieve valid values.
-    pub fn resolution(mut self, resolution: &str) -> Self {
-        self.resolution = Some(resolution.to_string());
+    /// Defaults to the first channel.
+    pub fn camera(mut self, camera: u8) -> Self {
+        self.options.camera = Some(camera);
         self
     }
 
@@ -34,27 +106,45 @@ impl GetImageRequest {
     ///
     /// A compression value yields lower image quality.
     pub fn compression(mut self, compression: u8) -> Self {
-        self.compression = Some(compression);
+        self.options.compression = Some(compression);
+        self
+    }
+
+    /// Rotate the image clockwise by this many degrees, e.g. `180`.
+    pub fn rotation(mut self, rotation: u16) -> Self {
+        self.options.rotation = Some(rotation);
+        self
+    }
+
+    /// Show or hide the text overlay.
+    pub fn text(mut self, enabled: bool) -> Self {
+        self.options.text = Some(enabled);
+        self
+    }
+
+    /// Show or hide the date in the text overlay.
+    pub fn date(mut self, enabled: bool) -> Self {
+        self.options.date = Some(enabled);
+        self
+    }
+
+    /// Show or hide the clock in the text overlay.
+    pub fn clock(mut self, enabled: bool) -> Self {
+        self.options.clock = Some(enabled);
+        self
+    }
+
+    /// Show or hide the image overlay.
+    pub fn overlay_image(mut self, enabled: bool) -> Self {
+        self.options.overlay_image = Some(enabled);
         self
     }
 
     // TODO: Migrate to `HttpClient` when cassette tests support binary responses.
     pub async fn send(self, client: &Client) -> anyhow::Result<Vec<u8>> {
-        let Self {
-            resolution,
-            compression,
-        } = self;
-        let mut query = Vec::new();
-        if let Some(resolution) = resolution.as_deref() {
-            query.push(("resolution", resolution));
-        }
-        let compression = compression.map(|c| c.to_string());
-        if let Some(compression) = compression.as_deref() {
-            query.push(("compression", compression));
-        }
         let resp = client
-            .get("axis-cgi/jpg/image.cgi")?
-            .query(&query)
+            .get(IMAGE_PATH)?
+            .query(&self.options.query())
             .send()
             .await?;
 
@@ -67,19 +157,272 @@ impl GetImageRequest {
             .map(|b| b.to_vec())
             .with_context(|| format!("Failed to get image; status: {status}"))?;
 
-        let magic = b"\xFF\xD8\xFF";
-        if !bytes.starts_with(magic) {
+        if !bytes.starts_with(JPEG_MAGIC) {
             #[expect(clippy::indexing_slicing, reason = "range is clipped to array length")]
             let prefix = &bytes[..bytes.len().min(3)];
-            bail!("Expected magic bytes {magic:?}, but got {prefix:?}");
+            bail!("Expected magic bytes {JPEG_MAGIC:?}, but got {prefix:?}");
         }
 
         Ok(bytes)
     }
 }
 
-impl Default for GetImageRequest {
-    fn default() -> Self {
-        Self::new()
+/// Open an MJPEG stream.
+#[derive(Clone, Debug, Default)]
+pub struct GetMjpegStreamRequest {
+    options: ImageOptions,
+    fps: Option<u16>,
+}
+
+impl GetMjpegStreamRequest {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// See [`GetImageRequest::resolution`].
+    pub fn resolution(mut self, resolution: Resolution) -> Self {
+        self.options.resolution = Some(resolution);
+        self
+    }
+
+    /// See [`GetImageRequest::camera`].
+    pub fn camera(mut self, camera: u8) -> Self {
+        self.options.camera = Some(camera);
+        self
+    }
+
+    /// See [`GetImageRequest::compression`].
+    pub fn compression(mut self, compression: u8) -> Self {
+        self.options.compression = Some(compression);
+        self
+    }
+
+    /// See [`GetImageRequest::rotation`].
+    pub fn rotation(mut self, rotation: u16) -> Self {
+        self.options.rotation = Some(rotation);
+        self
+    }
+
+    /// See [`GetImageRequest::text`].
+    pub fn text(mut self, enabled: bool) -> Self {
+        self.options.text = Some(enabled);
+        self
+    }
+
+    /// See [`GetImageRequest::date`].
+    pub fn date(mut self, enabled: bool) -> Self {
+        self.options.date = Some(enabled);
+        self
+    }
+
+    /// See [`GetImageRequest::clock`].
+    pub fn clock(mut self, enabled: bool) -> Self {
+        self.options.clock = Some(enabled);
+        self
+    }
+
+    /// See [`GetImageRequest::overlay_image`].
+    pub fn overlay_image(mut self, enabled: bool) -> Self {
+        self.options.overlay_image = Some(enabled);
+        self
+    }
+
+    /// Limit the frame rate; the device picks one if this is not set.
+    pub fn fps(mut self, fps: u16) -> Self {
+        self.fps = Some(fps);
+        self
+    }
+
+    pub async fn send(self, client: &Client) -> anyhow::Result<MjpegStream> {
+        let Self { options, fps } = self;
+        let mut query = options.query();
+        if let Some(fps) = fps {
+            query.push(("fps", fps.to_string()));
+        }
+        let response = client
+            .get(VIDEO_PATH)?
+            .query(&query)
+            .send()
+            .await?
+            .error_for_status()?;
+        let content_type = response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default();
+        let boundary = parse_boundary(content_type)
+            .with_context(|| format!("Expected a multipart response but got {content_type:?}"))?;
+        Ok(MjpegStream {
+            response,
+            delimiter: format!("--{boundary}").into_bytes(),
+            buffer: Vec::new(),
+        })
+    }
+}
+
+fn parse_boundary(content_type: &str) -> Option<String> {
+    let (mime, params) = content_type.split_once(';')?;
+    if !mime
+        .trim()
+        .eq_ignore_ascii_case("multipart/x-mixed-replace")
+    {
+        return None;
+    }
+    params.split(';').find_map(|p| {
+        let (key, value) = p.split_once('=')?;
+        if !key.trim().eq_ignore_ascii_case("boundary") {
+            return None;
+        }
+        let value = value.trim().trim_matches('"');
+        Some(value.strip_prefix("--").unwrap_or(value).to_string())
+    })
+}
+
+fn find(haystack: &[u8], needle: &[u8], from: usize) -> Option<usize> {
+    haystack
+        .get(from..)?
+        .windows(needle.len())
+        .position(|w| w == needle)
+        .map(|i| i + from)
+}
+
+/// Remove and return the first complete frame in `buffer`, if any.
+fn take_frame(buffer: &mut Vec<u8>, delimiter: &[u8]) -> anyhow::Result<Option<Vec<u8>>> {
+    let Some(start) = find(buffer, delimiter, 0) else {
+        return Ok(None);
+    };
+    let Some(headers_end) = find(buffer, b"\r\n\r\n", start) else {
+        return Ok(None);
+    };
+    let body_start = headers_end + 4;
+    let headers = buffer
+        .get(start + delimiter.len()..headers_end)
+        .context("Headers are out of bounds")?;
+    let content_length = String::from_utf8_lossy(headers).lines().find_map(|l| {
+        let (key, value) = l.split_once(':')?;
+        match key.trim().eq_ignore_ascii_case("content-length") {
+            true => value.trim().parse::<usize>().ok(),
+            false => None,
+        }
+    });
+    let (body_end, consumed) = match content_length {
+        Some(len) => (body_start + len, body_start + len),
+        None => {
+            let Some(next) = find(buffer, delimiter, body_start) else {
+                return Ok(None);
+            };
+            (next, next)
+        }
+    };
+    if buffer.len() < consumed {
+        return Ok(None);
+    }
+    let frame = buffer
+        .get(body_start..body_end)
+        .context("Frame is out of bounds")?;
+    let frame = frame.strip_suffix(b"\r\n").unwrap_or(frame).to_vec();
+    buffer.drain(..consumed);
+    if !frame.starts_with(JPEG_MAGIC) {
+        bail!("Expected a JPEG frame");
+    }
+    Ok(Some(frame))
+}
+
+/// The most data to buffer while waiting for a complete frame.
+///
+/// Frames are at most a few MB even at the highest resolutions, so hitting this limit means that
+/// the stream is not delimited as expected, e.g. because the boundary is wrong.
+const MAX_BUFFER_LEN: usize = 16 * 1024 * 1024;
+
+/// Append `chunk` to `buffer`, unless doing so would exceed [`MAX_BUFFER_LEN`].
+fn buffer_chunk(buffer: &mut Vec<u8>, chunk: &[u8]) -> anyhow::Result<()> {
+    if buffer.len() + chunk.len() > MAX_BUFFER_LEN {
+        bail!("Found no complete frame in the first {MAX_BUFFER_LEN} bytes");
+    }
+    buffer.extend_from_slice(chunk);
+    Ok(())
+}
+
+/// A stream of JPEG encoded frames.
+pub struct MjpegStream {
+    response: reqwest::Response,
+    delimiter: Vec<u8>,
+    buffer: Vec<u8>,
+}
+
+impl MjpegStream {
+    /// Wait for the next frame, or `None` if the device closed the stream.
+    pub async fn next_frame(&mut self) -> anyhow::Result<Option<Vec<u8>>> {
+        loop {
+            if let Some(frame) = take_frame(&mut self.buffer, &self.delimiter)? {
+                return Ok(Some(frame));
+            }
+            match self.response.chunk().await? {
+                Some(chunk) => buffer_chunk(&mut self.buffer, &chunk)?,
+                None => return Ok(None),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_parse_boundary() {
+        assert_eq!(
+            parse_boundary("multipart/x-mixed-replace; boundary=myboundary").as_deref(),
+            Some("myboundary")
+        );
+        assert_eq!(
+            parse_boundary(r#"multipart/x-mixed-replace;boundary="--myboundary""#).as_deref(),
+            Some("myboundary")
+        );
+        assert_eq!(parse_boundary("image/jpeg"), None);
+    }
+
+    #[test]
+    fn can_take_frames() {
+        let delimiter = b"--myboundary";
+        let mut buffer = Vec::new();
+        buffer.extend_from_slice(b"--myboundary\r\nContent-Type: image/jpeg\r\n");
+        buffer.extend_from_slice(b"Content-Length: 5\r\n\r\n\xFF\xD8\xFF\x00\x01\r\n");
+        buffer.extend_from_slice(b"--myboundary\r\nContent-Type: image/jpeg\r\n\r\n");
+        buffer.extend_from_slice(b"\xFF\xD8\xFF\x02\r\n--myboundary\r\n");
+
+        let first = take_frame(&mut buffer, delimiter).unwrap().unwrap();
+        assert_eq!(first, b"\xFF\xD8\xFF\x00\x01");
+        let second = take_frame(&mut buffer, delimiter).unwrap().unwrap();
+        assert_eq!(second, b"\xFF\xD8\xFF\x02");
+        assert_eq!(take_frame(&mut buffer, delimiter).unwrap(), None);
+        assert_eq!(buffer, b"--myboundary\r\n");
+    }
+
+    #[test]
+    fn buffer_is_bounded() {
+        let mut buffer = vec![0; MAX_BUFFER_LEN - 1];
+        buffer_chunk(&mut buffer, b"a").unwrap();
+        assert!(buffer_chunk(&mut buffer, b"b").is_err());
+        assert_eq!(buffer.len(), MAX_BUFFER_LEN);
+    }
+
+    #[test]
+    fn query_includes_only_set_options() {
+        let request = GetImageRequest::new()
+            .resolution(Resolution {
+                width_px: 640,
+                height_px: 360,
+            })
+            .camera(2)
+            .text(false);
+        assert_eq!(
+            request.options.query(),
+            [
+                ("resolution", "640x360".to_string()),
+                ("camera", "2".to_string()),
+                ("text", "0".to_string()),
+            ]
+        );
     }
 }
@@ -12,6 +12,8 @@ use crate::{
 
 pub const API_ID: ApiId = ApiId::new("network-settings");
 
+const API_VERSION: &str = "1.0";
+
 const PATH: &str = "axis-cgi/network_settings.cgi";
 
 #[derive(Debug, Serialize)]
@@ -88,6 +90,333 @@ impl SetGlobalProxyConfigurationRequest {
 #[derive(Debug, Deserialize, Serialize)]
 pub struct SetGlobalProxyConfigurationData {}
 
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SetHostnameConfigurationParams {
+    use_dhcp_hostname: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    static_hostname: Option<String>,
+}
+
+/// Set the hostname, or let DHCP provide it.
+#[derive(Debug)]
+pub struct SetHostnameConfigurationRequest {
+    params: SetHostnameConfigurationParams,
+}
+
+impl SetHostnameConfigurationRequest {
+    /// Use `hostname` regardless of what DHCP provides.
+    pub fn static_hostname(hostname: impl Into<String>) -> Self {
+        Self {
+            params: SetHostnameConfigurationParams {
+                use_dhcp_hostname: false,
+                static_hostname: Some(hostname.into()),
+            },
+        }
+    }
+
+    /// Use the hostname provided by DHCP, if any.
+    pub fn dhcp() -> Self {
+        Self {
+            params: SetHostnameConfigurationParams {
+                use_dhcp_hostname: true,
+                static_hostname: None,
+            },
+        }
+    }
+
+    pub async fn send(
+        self,
+        client: &(impl HttpClient + Sync),
+    ) -> Result<SetHostnameConfigurationData, Error<json_rpc::Error>> {
+        let body =
+            json_rpc::RequestBody::new(API_VERSION, "setHostnameConfiguration", Some(self.params));
+        json_rpc_http::send_request(client, PATH, &body).await
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct SetHostnameConfigurationData {}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SetResolverConfigurationParams {
+    use_dhcp_resolver_info: bool,
+    static_name_servers: Vec<String>,
+    static_search_domains: Vec<String>,
+    static_domain_name: String,
+}
+
+/// Configure the DNS resolver.
+#[derive(Debug)]
+pub struct SetResolverConfigurationRequest {
+    params: SetResolverConfigurationParams,
+}
+
+impl Default for SetResolverConfigurationRequest {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SetResolverConfigurationRequest {
+    /// Use only the resolver information provided by DHCP.
+    pub fn new() -> Self {
+        Self {
+            params: SetResolverConfigurationParams {
+                use_dhcp_resolver_info: true,
+                static_name_servers: Vec::new(),
+                static_search_domains: Vec::new(),
+                static_domain_name: String::new(),
+            },
+        }
+    }
+
+    /// Start from the current configuration, e.g. to change only the name servers.
+    pub fn from_resolver(resolver: &Resolver) -> Self {
+        Self {
+            params: SetResolverConfigurationParams {
+                use_dhcp_resolver_info: resolver.use_dhcp_resolver_info,
+                static_name_servers: resolver.static_name_servers.clone(),
+                static_search_domains: resolver.static_search_domains.clone(),
+                static_domain_name: resolver.static_domain_name.clone(),
+            },
+        }
+    }
+
+    pub fn use_dhcp_resolver_info(mut self, use_dhcp: bool) -> Self {
+        self.params.use_dhcp_resolver_info = use_dhcp;
+        self
+    }
+
+    /// Replace the static name servers.
+    ///
+    /// These are used only if the DHCP resolver info is not.
+    pub fn static_name_servers(mut self, name_servers: Vec<String>) -> Self {
+        self.params.static_name_servers = name_servers;
+        self
+    }
+
+    pub fn static_search_domains(mut self, search_domains: Vec<String>) -> Self {
+        self.params.static_search_domains = search_domains;
+        self
+    }
+
+    pub fn static_domain_name(mut self, domain_name: impl Into<String>) -> Self {
+        self.params.static_domain_name = domain_name.into();
+        self
+    }
+
+    pub async fn send(
+        self,
+        client: &(impl HttpClient + Sync),
+    ) -> Result<SetResolverConfigurationData, Error<json_rpc::Error>> {
+        let body =
+            json_rpc::RequestBody::new(API_VERSION, "setResolverConfiguration", Some(self.params));
+        json_rpc_http::send_request(client, PATH, &body).await
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct SetResolverConfigurationData {}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SetIpv4AddressConfigurationParams {
+    device_name: String,
+    configuration_mode: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    static_address_configurations: Option<Vec<StaticAddressConfiguration>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    static_default_router: Option<String>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        rename = "useStaticDHCPFallback"
+    )]
+    use_static_dhcp_fallback: Option<bool>,
+}
+
+/// Configure the IPv4 address of a network device, e.g. `"eth0"`.
+#[derive(Debug)]
+pub struct SetIpv4AddressConfigurationRequest {
+    params: SetIpv4AddressConfigurationParams,
+}
+
+impl SetIpv4AddressConfigurationRequest {
+    /// Obtain the address from DHCP.
+    pub fn dhcp(device_name: impl Into<String>) -> Self {
+        Self {
+            params: SetIpv4AddressConfigurationParams {
+                device_name: device_name.into(),
+                configuration_mode: "dhcp",
+                static_address_configurations: None,
+                static_default_router: None,
+                use_static_dhcp_fallback: None,
+            },
+        }
+    }
+
+    /// Use a static address and default router.
+    pub fn static_address(
+        device_name: impl Into<String>,
+        address: StaticAddressConfiguration,
+        default_router: impl Into<String>,
+    ) -> Self {
+        Self {
+            params: SetIpv4AddressConfigurationParams {
+                device_name: device_name.into(),
+                configuration_mode: "static",
+                static_address_configurations: Some(vec![address]),
+                static_default_router: Some(default_router.into()),
+                use_static_dhcp_fallback: None,
+            },
+        }
+    }
+
+    /// Fall back to the static configuration if no DHCP server responds.
+    pub fn static_dhcp_fallback(mut self, fallback: bool) -> Self {
+        self.params.use_static_dhcp_fallback = Some(fallback);
+        self
+    }
+
+    pub async fn send(
+        self,
+        client: &(impl HttpClient + Sync),
+    ) -> Result<SetIpv4AddressConfigurationData, Error<json_rpc::Error>> {
+        let body = json_rpc::RequestBody::new(
+            API_VERSION,
+            "setIPv4AddressConfiguration",
+            Some(self.params),
+        );
+        json_rpc_http::send_request(client, PATH, &body).await
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct SetIpv4AddressConfigurationData {}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SetIpv6AddressConfigurationParams {
+    device_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none", rename = "acceptRA")]
+    accept_ra: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none", rename = "enableDHCPv6")]
+    enable_dhcpv6: Option<bool>,
+}
+
+/// Toggle IPv6 features of a network device, e.g. `"eth0"`.
+///
+/// Features that are not set are left unchanged.
+#[derive(Debug)]
+pub struct SetIpv6AddressConfigurationRequest {
+    params: SetIpv6AddressConfigurationParams,
+}
+
+impl SetIpv6AddressConfigurationRequest {
+    pub fn new(device_name: impl Into<String>) -> Self {
+        Self {
+            params: SetIpv6AddressConfigurationParams {
+                device_name: device_name.into(),
+                enabled: None,
+                accept_ra: None,
+                enable_dhcpv6: None,
+            },
+        }
+    }
+
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.params.enabled = Some(enabled);
+        self
+    }
+
+    /// Configure addresses from router advertisements.
+    pub fn accept_router_advertisements(mut self, accept: bool) -> Self {
+        self.params.accept_ra = Some(accept);
+        self
+    }
+
+    pub fn dhcpv6(mut self, enabled: bool) -> Self {
+        self.params.enable_dhcpv6 = Some(enabled);
+        self
+    }
+
+    pub async fn send(
+        self,
+        client: &(impl HttpClient + Sync),
+    ) -> Result<SetIpv6AddressConfigurationData, Error<json_rpc::Error>> {
+        let body = json_rpc::RequestBody::new(
+            API_VERSION,
+            "setIPv6AddressConfiguration",
+            Some(self.params),
+        );
+        json_rpc_http::send_request(client, PATH, &body).await
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct SetIpv6AddressConfigurationData {}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Set8021XConfigurationParams {
+    device_name: String,
+    enabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    mode: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    params: Option<Dot1xParams>,
+}
+
+/// Configure IEEE 802.1X authentication of a wired network device, e.g. `"eth0"`.
+#[derive(Debug)]
+pub struct Set8021XConfigurationRequest {
+    params: Set8021XConfigurationParams,
+}
+
+impl Set8021XConfigurationRequest {
+    /// Enable 802.1X using `mode`, one of the `supported_modes` in [`Dot1xInfo`].
+    pub fn enable(
+        device_name: impl Into<String>,
+        mode: impl Into<String>,
+        params: Dot1xParams,
+    ) -> Self {
+        Self {
+            params: Set8021XConfigurationParams {
+                device_name: device_name.into(),
+                enabled: true,
+                mode: Some(mode.into()),
+                params: Some(params),
+            },
+        }
+    }
+
+    pub fn disable(device_name: impl Into<String>) -> Self {
+        Self {
+            params: Set8021XConfigurationParams {
+                device_name: device_name.into(),
+                enabled: false,
+                mode: None,
+                params: None,
+            },
+        }
+    }
+
+    pub async fn send(
+        self,
+        client: &(impl HttpClient + Sync),
+    ) -> Result<Set8021XConfigurationData, Error<json_rpc::Error>> {
+        let body =
+            json_rpc::RequestBody::new(API_VERSION, "set8021XConfiguration", Some(self.params));
+        json_rpc_http::send_request(client, PATH, &body).await
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Set8021XConfigurationData {}
+
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 struct GetNetworkInfoBody {
@@ -159,7 +488,7 @@ pub struct Hostname {
     pub static_hostname: String,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Resolver {
     pub use_dhcp_resolver_info: bool,
@@ -232,11 +561,14 @@ pub struct Dot1xConfiguration {
     pub params: Dot1xParams,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Dot1xParams {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub identity: Option<String>,
+    /// Write-only; never included in responses.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none", rename = "is_password_set")]
     pub is_password_set: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -290,7 +622,7 @@ pub struct Ipv4Address {
     pub broadcast: Option<String>,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct StaticAddressConfiguration {
     pub address: String,
@@ -0,0 +1,124 @@
+//! The [NTP API].
+//!
+//! [NTP API]: https://developer.axis.com/vapix/network-video/ntp-api/
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    apis::api_discovery_1::ApiId,
+    http::HttpClient,
+    protocol_helpers::{http::Error, json_rpc, json_rpc_http},
+};
+
+pub const API_ID: ApiId = ApiId::new("ntp");
+
+const API_VERSION: &str = "1.0";
+
+const PATH: &str = "axis-cgi/ntp.cgi";
+
+/// Where the NTP client gets its servers from.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum ServersSource {
+    #[serde(rename = "DHCP")]
+    Dhcp,
+    #[serde(rename = "static")]
+    Static,
+}
+
+#[derive(Debug, Default)]
+pub struct GetNtpInfoRequest;
+
+impl GetNtpInfoRequest {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub async fn send(
+        self,
+        client: &(impl HttpClient + Sync),
+    ) -> Result<NtpInfoData, Error<json_rpc::Error>> {
+        let body = json_rpc::RequestBody::<()>::new(API_VERSION, "getNTPInfo", None);
+        json_rpc_http::send_request(client, PATH, &body).await
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NtpInfoData {
+    pub client: NtpClient,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time_in_sync: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NtpClient {
+    pub enabled: bool,
+    pub servers_source: ServersSource,
+    /// The servers currently in use, regardless of their source.
+    pub servers: Vec<String>,
+    pub static_servers: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_static_servers: Option<u32>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SetNtpClientConfigurationParams {
+    enabled: bool,
+    servers_source: ServersSource,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    static_servers: Option<Vec<String>>,
+}
+
+#[derive(Debug)]
+pub struct SetNtpClientConfigurationRequest {
+    params: SetNtpClientConfigurationParams,
+}
+
+impl SetNtpClientConfigurationRequest {
+    /// Synchronize the clock with the servers provided by DHCP.
+    pub fn dhcp() -> Self {
+        Self {
+            params: SetNtpClientConfigurationParams {
+                enabled: true,
+                servers_source: ServersSource::Dhcp,
+                static_servers: None,
+            },
+        }
+    }
+
+    /// Synchronize the clock with `servers`, e.g. `["pool.ntp.org"]`.
+    pub fn static_servers(servers: Vec<String>) -> Self {
+        Self {
+            params: SetNtpClientConfigurationParams {
+                enabled: true,
+                servers_source: ServersSource::Static,
+                static_servers: Some(servers),
+            },
+        }
+    }
+
+    /// Stop synchronizing the clock, e.g. before setting it manually.
+    pub fn disabled() -> Self {
+        Self {
+            params: SetNtpClientConfigurationParams {
+                enabled: false,
+                servers_source: ServersSource::Dhcp,
+                static_servers: None,
+            },
+        }
+    }
+
+    pub async fn send(
+        self,
+        client: &(impl HttpClient + Sync),
+    ) -> Result<SetNtpClientConfigurationData, Error<json_rpc::Error>> {
+        let body =
+            json_rpc::RequestBody::new(API_VERSION, "setNTPClientConfiguration", Some(self.params));
+        json_rpc_http::send_request(client, PATH, &body).await
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct SetNtpClientConfigurationData {}
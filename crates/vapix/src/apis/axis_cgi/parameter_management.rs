@@ -2,15 +2,25 @@
 //!
 //! [Parameter Management]: https://developer.axis.com/vapix/network-video/parameter-management/
 
-use std::{collections::HashMap, fmt, fmt::Debug};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt,
+    fmt::Debug,
+};
 
 use anyhow::{bail, Context};
 use reqwest::Method;
 
-use crate::http::{HttpClient, Request};
+use crate::{
+    http::{HttpClient, Request},
+    protocol_helpers::http::encode_query_value,
+};
 
 const PATH: &str = "axis-cgi/param.cgi";
 
+/// Updates are split into several requests to keep the URL within limits of the device.
+const MAX_PATH_LEN: usize = 2000;
+
 fn bool2str(b: bool) -> &'static str {
     match b {
         true => "yes",
@@ -26,11 +36,6 @@ fn str2bool(s: &str) -> anyhow::Result<bool> {
     }
 }
 
-#[derive(Clone, Debug, Default)]
-pub struct UpdateRequest {
-    parameters: HashMap<String, String>,
-}
-
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Resolution {
     pub width_px: u32,
@@ -64,6 +69,11 @@ pub trait Parameter {
     fn parse(raw: &str) -> anyhow::Result<Self::Value>;
 }
 
+/// A [`Parameter`] that can be changed with an [`UpdateRequest`].
+pub trait WritableParameter: Parameter {
+    fn format(value: &Self::Value) -> String;
+}
+
 /// [`Parameter`] for `Properties.Image.Resolution`.
 ///
 /// These are the available pixel resolutions for image sources.
@@ -88,6 +98,170 @@ impl Parameter for NetworkSshEnabled {
     }
 }
 
+impl WritableParameter for NetworkSshEnabled {
+    fn format(value: &bool) -> String {
+        bool2str(*value).to_string()
+    }
+}
+
+/// The product number, e.g. `"M3086-V"`.
+pub struct BrandProdNbr;
+
+impl Parameter for BrandProdNbr {
+    type Value = String;
+    const KEY: &'static str = "root.Brand.ProdNbr";
+    fn parse(raw: &str) -> anyhow::Result<String> {
+        Ok(raw.to_string())
+    }
+}
+
+/// The full product name, e.g. `"AXIS M3086-V Network Camera"`.
+pub struct BrandProdFullName;
+
+impl Parameter for BrandProdFullName {
+    type Value = String;
+    const KEY: &'static str = "root.Brand.ProdFullName";
+    fn parse(raw: &str) -> anyhow::Result<String> {
+        Ok(raw.to_string())
+    }
+}
+
+/// The version of AXIS OS, e.g. `"11.11.73"`.
+pub struct PropertiesFirmwareVersion;
+
+impl Parameter for PropertiesFirmwareVersion {
+    type Value = String;
+    const KEY: &'static str = "root.Properties.Firmware.Version";
+    fn parse(raw: &str) -> anyhow::Result<String> {
+        Ok(raw.to_string())
+    }
+}
+
+pub struct PropertiesSystemSerialNumber;
+
+impl Parameter for PropertiesSystemSerialNumber {
+    type Value = String;
+    const KEY: &'static str = "root.Properties.System.SerialNumber";
+    fn parse(raw: &str) -> anyhow::Result<String> {
+        Ok(raw.to_string())
+    }
+}
+
+pub struct NetworkHostName;
+
+impl Parameter for NetworkHostName {
+    type Value = String;
+    const KEY: &'static str = "root.Network.HostName";
+    fn parse(raw: &str) -> anyhow::Result<String> {
+        Ok(raw.to_string())
+    }
+}
+
+impl WritableParameter for NetworkHostName {
+    fn format(value: &String) -> String {
+        value.clone()
+    }
+}
+
+pub struct NetworkBonjourEnabled;
+
+impl Parameter for NetworkBonjourEnabled {
+    type Value = bool;
+    const KEY: &'static str = "root.Network.Bonjour.Enabled";
+    fn parse(raw: &str) -> anyhow::Result<bool> {
+        str2bool(raw)
+    }
+}
+
+impl WritableParameter for NetworkBonjourEnabled {
+    fn format(value: &bool) -> String {
+        bool2str(*value).to_string()
+    }
+}
+
+pub struct NetworkUpnpEnabled;
+
+impl Parameter for NetworkUpnpEnabled {
+    type Value = bool;
+    const KEY: &'static str = "root.Network.UPnP.Enabled";
+    fn parse(raw: &str) -> anyhow::Result<bool> {
+        str2bool(raw)
+    }
+}
+
+impl WritableParameter for NetworkUpnpEnabled {
+    fn format(value: &bool) -> String {
+        bool2str(*value).to_string()
+    }
+}
+
+pub struct HttpsPort;
+
+impl Parameter for HttpsPort {
+    type Value = u16;
+    const KEY: &'static str = "root.HTTPS.Port";
+    fn parse(raw: &str) -> anyhow::Result<u16> {
+        Ok(raw.parse()?)
+    }
+}
+
+impl WritableParameter for HttpsPort {
+    fn format(value: &u16) -> String {
+        value.to_string()
+    }
+}
+
+/// The default resolution of the first video channel.
+pub struct ImageI0AppearanceResolution;
+
+impl Parameter for ImageI0AppearanceResolution {
+    type Value = Resolution;
+    const KEY: &'static str = "root.Image.I0.Appearance.Resolution";
+    fn parse(raw: &str) -> anyhow::Result<Resolution> {
+        raw.parse()
+    }
+}
+
+impl WritableParameter for ImageI0AppearanceResolution {
+    fn format(value: &Resolution) -> String {
+        value.to_string()
+    }
+}
+
+/// The default rotation in degrees of the first video channel.
+pub struct ImageI0AppearanceRotation;
+
+impl Parameter for ImageI0AppearanceRotation {
+    type Value = u16;
+    const KEY: &'static str = "root.Image.I0.Appearance.Rotation";
+    fn parse(raw: &str) -> anyhow::Result<u16> {
+        Ok(raw.parse()?)
+    }
+}
+
+impl WritableParameter for ImageI0AppearanceRotation {
+    fn format(value: &u16) -> String {
+        value.to_string()
+    }
+}
+
+/// Whether the text overlay is shown on the first video channel.
+pub struct ImageI0TextTextEnabled;
+
+impl Parameter for ImageI0TextTextEnabled {
+    type Value = bool;
+    const KEY: &'static str = "root.Image.I0.Text.TextEnabled";
+    fn parse(raw: &str) -> anyhow::Result<bool> {
+        str2bool(raw)
+    }
+}
+
+impl WritableParameter for ImageI0TextTextEnabled {
+    fn format(value: &bool) -> String {
+        bool2str(*value).to_string()
+    }
+}
+
 // TODO: Implement lossless checking
 /// The response from a parameter list request.
 #[derive(Clone, Debug)]
@@ -103,73 +277,252 @@ impl ParamList {
     pub fn parse<K: Parameter>(&self) -> anyhow::Result<Option<K::Value>> {
         self.0.get(K::KEY).map(|v| K::parse(v)).transpose()
     }
+
+    /// Iterate over all parameters in the response, sorted by key.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        let mut params: Vec<_> = self
+            .0
+            .iter()
+            .map(|(k, v)| (k.as_str(), v.as_str()))
+            .collect();
+        params.sort();
+        params.into_iter()
+    }
+
+    /// Arrange the parameters in a tree with one node for every dot separated part of the keys.
+    pub fn tree(&self) -> ParamTree {
+        let mut tree = ParamTree::default();
+        for (key, value) in &self.0 {
+            let node = key.split('.').fold(&mut tree, |node, name| {
+                node.children.entry(name.to_string()).or_default()
+            });
+            node.value = Some(value.clone());
+        }
+        tree
+    }
+}
+
+/// A group of parameters, a parameter, or both.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ParamTree {
+    value: Option<String>,
+    children: BTreeMap<String, ParamTree>,
+}
+
+impl ParamTree {
+    /// The value of the parameter at this node, if it is a parameter.
+    pub fn value(&self) -> Option<&str> {
+        self.value.as_deref()
+    }
+
+    pub fn child(&self, name: &str) -> Option<&ParamTree> {
+        self.children.get(name)
+    }
+
+    /// Get the descendant at the dot separated `path`, e.g. `"root.Image.I0"`.
+    pub fn get(&self, path: &str) -> Option<&ParamTree> {
+        path.split('.')
+            .try_fold(self, |node, name| node.child(name))
+    }
+
+    /// Iterate over the direct children, sorted by name.
+    pub fn children(&self) -> impl Iterator<Item = (&str, &ParamTree)> {
+        self.children.iter().map(|(k, v)| (k.as_str(), v))
+    }
+
+    /// Iterate over the parameters at or below this node as `(relative key, value)`.
+    pub fn leaves(&self) -> Vec<(String, &str)> {
+        let mut leaves = Vec::new();
+        if let Some(value) = self.value.as_deref() {
+            leaves.push((String::new(), value));
+        }
+        for (name, child) in &self.children {
+            for (key, value) in child.leaves() {
+                let key = match key.is_empty() {
+                    true => name.clone(),
+                    false => format!("{name}.{key}"),
+                };
+                leaves.push((key, value));
+            }
+        }
+        leaves
+    }
+}
+
+fn parse_param_list(text: &str) -> anyhow::Result<ParamList> {
+    if let Some(e) = text.trim().strip_prefix("# Error: ") {
+        bail!("{e}");
+    }
+
+    let mut params = HashMap::new();
+    for line in text.lines() {
+        if let Some((k, v)) = line.split_once('=') {
+            params.insert(k.to_string(), v.to_string());
+        }
+    }
+    Ok(ParamList(params))
 }
 
 #[derive(Clone, Debug)]
 pub struct ListRequest {
-    group: String,
+    groups: Vec<String>,
 }
 
 impl ListRequest {
-    // TODO: Add support for retrieving groups of parameters.
     pub fn new<T: Parameter>() -> Self {
+        Self::group(T::KEY)
+    }
+
+    /// List every parameter in `group`, e.g. `"root.Image"`.
+    pub fn group(group: &str) -> Self {
         Self {
-            group: T::KEY.to_string(),
+            groups: vec![group.to_string()],
         }
     }
 
+    /// Also list the parameter `T`.
+    pub fn and<T: Parameter>(self) -> Self {
+        self.and_group(T::KEY)
+    }
+
+    /// Also list every parameter in `group`.
+    pub fn and_group(mut self, group: &str) -> Self {
+        self.groups.push(group.to_string());
+        self
+    }
+
+    fn path(&self) -> String {
+        let groups: Vec<_> = self.groups.iter().map(|g| encode_query_value(g)).collect();
+        format!("{PATH}?action=list&group={}", groups.join(","))
+    }
+
     pub async fn send(self, client: &impl HttpClient) -> anyhow::Result<ParamList> {
-        let path = format!("{PATH}?action=list&group={}", self.group);
         let response = client
-            .execute(Request::new(Method::GET, path))
+            .execute(Request::new(Method::GET, self.path()))
             .await
             .context("sending param.cgi request")?;
 
         let text = response.body.context("reading param.cgi response")?;
 
-        if let Some(e) = text.trim().strip_prefix("# Error: ") {
-            bail!("{e}");
+        parse_param_list(&text)
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct UpdateRequest {
+    parameters: BTreeMap<String, String>,
+}
+
+impl UpdateRequest {
+    pub fn network_ssh_enabled(self, value: bool) -> Self {
+        self.set::<NetworkSshEnabled>(value)
+    }
+
+    /// Set the parameter `P` to `value`.
+    pub fn set<P: WritableParameter>(self, value: P::Value) -> Self {
+        self.set_raw(P::KEY, &P::format(&value))
+    }
+
+    /// Set the parameter `key` to `value` as is, e.g. when restoring a [`ParamList`].
+    pub fn set_raw(mut self, key: &str, value: &str) -> Self {
+        self.parameters.insert(key.to_string(), value.to_string());
+        self
+    }
+
+    /// Build the paths for the update, splitting it into several requests if needed.
+    fn paths(&self) -> Vec<String> {
+        let prefix = format!("{PATH}?action=update");
+        let mut paths = Vec::new();
+        let mut path = prefix.clone();
+        for (k, v) in &self.parameters {
+            let param = format!("&{}={}", encode_query_value(k), encode_query_value(v));
+            if path.len() > prefix.len() && path.len() + param.len() > MAX_PATH_LEN {
+                paths.push(std::mem::replace(&mut path, prefix.clone()));
+            }
+            path.push_str(&param);
         }
+        paths.push(path);
+        paths
+    }
+
+    /// Apply the updates.
+    ///
+    /// Large updates are sent as several requests and, if one fails, the preceding ones remain
+    /// applied.
+    pub async fn send(self, client: &(impl HttpClient + Sync)) -> anyhow::Result<()> {
+        for path in self.paths() {
+            let response = client
+                .execute(Request::new(Method::GET, path))
+                .await
+                .context("sending param.cgi update request")?;
+            let text = response.body.context("reading param.cgi update response")?;
 
-        let mut params = HashMap::new();
-        for line in text.lines() {
-            if let Some((k, v)) = line.split_once('=') {
-                params.insert(k.to_string(), v.to_string());
+            if response.status.is_success() && text.trim() == "OK" {
+                continue;
+            } else if let Some(e) = text.trim().strip_prefix("# Error: ") {
+                bail!("{e}")
+            } else {
+                bail!("Unexpected response: {} {text}", response.status)
             }
         }
-        Ok(ParamList(params))
+        Ok(())
     }
 }
 
-impl UpdateRequest {
-    pub fn network_ssh_enabled(mut self, value: bool) -> Self {
-        self.parameters.insert(
-            "root.Network.SSH.Enabled".to_string(),
-            bool2str(value).to_string(),
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_navigate_group_listing() {
+        let text = include_str!("parameter_management/list_image_200.txt");
+        let params = parse_param_list(text).unwrap();
+        assert_eq!(
+            params.parse::<ImageI0AppearanceResolution>().unwrap(),
+            Some(Resolution {
+                width_px: 1920,
+                height_px: 1080
+            })
+        );
+
+        let tree = params.tree();
+        let i0 = tree.get("root.Image.I0").unwrap();
+        assert_eq!(i0.value(), None);
+        let groups: Vec<_> = i0.children().map(|(name, _)| name).collect();
+        assert_eq!(groups, ["Appearance", "Text"]);
+        assert_eq!(i0.get("Appearance.Rotation").unwrap().value(), Some("180"));
+        assert_eq!(
+            i0.child("Text").unwrap().leaves(),
+            [
+                ("String".to_string(), "Hello, world!"),
+                ("TextEnabled".to_string(), "yes")
+            ]
         );
-        self
     }
 
-    pub async fn send(self, client: &(impl HttpClient + Sync)) -> anyhow::Result<()> {
-        let mut path = format!("{PATH}?action=update");
-        for (k, v) in &self.parameters {
-            path.push('&');
-            path.push_str(k);
-            path.push('=');
-            path.push_str(v);
-        }
-        let response = client
-            .execute(Request::new(Method::GET, path))
-            .await
-            .context("sending param.cgi update request")?;
-        let text = response.body.context("reading param.cgi update response")?;
-
-        if response.status.is_success() && text.trim() == "OK" {
-            Ok(())
-        } else if let Some(e) = text.trim().strip_prefix("# Error: ") {
-            bail!("{e}")
-        } else {
-            bail!("Unexpected response: {} {text}", response.status)
+    #[test]
+    fn update_values_are_encoded() {
+        let paths = UpdateRequest::default()
+            .set::<NetworkHostName>("lab-1".to_string())
+            .set_raw("root.Image.I0.Text.String", "a&b=c %")
+            .set::<ImageI0TextTextEnabled>(true)
+            .paths();
+        assert_eq!(
+            paths,
+            ["axis-cgi/param.cgi?action=update&root.Image.I0.Text.String=a%26b%3Dc+%25&root.Image.I0.Text.TextEnabled=yes&root.Network.HostName=lab-1"]
+        );
+    }
+
+    #[test]
+    fn large_updates_are_batched() {
+        let mut request = UpdateRequest::default();
+        for i in 0..100 {
+            request = request.set_raw(&format!("root.Group.G{i}.Value"), &"x".repeat(50));
         }
+        let paths = request.paths();
+        assert!(paths.len() > 1);
+        assert!(paths.iter().all(|p| p.len() <= MAX_PATH_LEN));
+        let params: usize = paths.iter().map(|p| p.matches('&').count()).sum();
+        assert_eq!(params, 100);
     }
 }
@@ -2,13 +2,17 @@
 //!
 //! [User Management]: https://developer.axis.com/vapix/network-video/user-management/
 
-use std::fmt::{Display, Formatter};
+use std::{
+    collections::BTreeMap,
+    fmt::{Display, Formatter},
+};
 
+use anyhow::Context;
 use reqwest::{Method, StatusCode};
 
 use crate::{
-    http::{HttpClient, Request},
-    protocol_helpers::http::Error as HttpError,
+    http::{HttpClient, Request, Response},
+    protocol_helpers::http::{encode_query_value, Error as HttpError},
 };
 
 const PATH: &str = "axis-cgi/pwdgrp.cgi";
@@ -40,24 +44,63 @@ impl Display for Error {
 
 impl std::error::Error for Error {}
 
-#[derive(Clone, Copy, Debug)]
+/// Parse the HTML reply shared by the add, update and remove actions.
+fn parse_reply(response: Response, expected: &str) -> Result<(), HttpError<Error>> {
+    let body = response.body.map_err(|e| HttpError::Transport(e.into()))?;
+    let html_body = extract_body(&body).unwrap_or("");
+    let trimmed = html_body.trim();
+    if let Some(message) = trimmed.strip_prefix("Error: ") {
+        let message = message.strip_suffix('.').unwrap_or(message);
+        return Err(HttpError::Service(Error {
+            message: message.to_string(),
+        }));
+    }
+    if response.status == StatusCode::OK && trimmed == expected {
+        return Ok(());
+    }
+    Err(HttpError::Decode(anyhow::anyhow!(
+        "Unexpected response: {} {}",
+        response.status,
+        body.trim()
+    )))
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum Role {
     Viewer,
     OperatorViewer,
+    OperatorViewerPtz,
+    AdminOperatorViewer,
     AdminOperatorViewerPtz,
 }
 
+impl Role {
+    /// Infer the role from the names of the security groups that a user is a member of.
+    fn from_security_groups(admin: bool, operator: bool, viewer: bool, ptz: bool) -> Option<Self> {
+        match (admin, operator, viewer, ptz) {
+            (false, false, true, false) => Some(Self::Viewer),
+            (false, true, true, false) => Some(Self::OperatorViewer),
+            (false, true, true, true) => Some(Self::OperatorViewerPtz),
+            (true, true, true, false) => Some(Self::AdminOperatorViewer),
+            (true, true, true, true) => Some(Self::AdminOperatorViewerPtz),
+            _ => None,
+        }
+    }
+}
+
 impl Display for Role {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
             Role::Viewer => write!(f, "viewer"),
             Role::OperatorViewer => write!(f, "operator:viewer"),
+            Role::OperatorViewerPtz => write!(f, "operator:viewer:ptz"),
+            Role::AdminOperatorViewer => write!(f, "admin:operator:viewer"),
             Role::AdminOperatorViewerPtz => write!(f, "admin:operator:viewer:ptz"),
         }
     }
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum Group {
     Root,
     Users,
@@ -97,7 +140,10 @@ impl AddUserRequest {
             Method::GET,
             format!(
                 "{PATH}?action=add&user={}&pwd={}&grp={}&sgrp={}",
-                self.username, self.password, group, role
+                encode_query_value(&self.username),
+                encode_query_value(&self.password),
+                group,
+                role
             ),
         )
     }
@@ -108,23 +154,70 @@ impl AddUserRequest {
             .execute(self.into_request())
             .await
             .map_err(HttpError::Transport)?;
-        let body = response.body.map_err(|e| HttpError::Transport(e.into()))?;
-        let html_body = extract_body(&body).unwrap_or("");
-        let trimmed = html_body.trim();
-        if let Some(message) = trimmed.strip_prefix("Error: ") {
-            let message = message.strip_suffix('.').unwrap_or(message);
-            return Err(HttpError::Service(Error {
-                message: message.to_string(),
-            }));
+        parse_reply(response, &expected)
+    }
+}
+
+/// Change the password, group or role of an existing user.
+///
+/// Properties that are not set are left unchanged.
+#[derive(Clone, Debug)]
+pub struct UpdateUserRequest {
+    username: String,
+    password: Option<String>,
+    group: Option<Group>,
+    role: Option<Role>,
+}
+
+impl UpdateUserRequest {
+    pub fn new(username: &str) -> Self {
+        Self {
+            username: username.to_string(),
+            password: None,
+            group: None,
+            role: None,
         }
-        if response.status == StatusCode::OK && trimmed == expected {
-            return Ok(());
+    }
+
+    pub fn password(mut self, password: &str) -> Self {
+        self.password = Some(password.to_string());
+        self
+    }
+
+    pub fn group(mut self, group: Group) -> Self {
+        self.group = Some(group);
+        self
+    }
+
+    pub fn role(mut self, role: Role) -> Self {
+        self.role = Some(role);
+        self
+    }
+
+    fn into_request(self) -> Request {
+        let mut path = format!(
+            "{PATH}?action=update&user={}",
+            encode_query_value(&self.username)
+        );
+        if let Some(password) = &self.password {
+            path.push_str(&format!("&pwd={}", encode_query_value(password)));
+        }
+        if let Some(group) = self.group {
+            path.push_str(&format!("&grp={group}"));
         }
-        Err(HttpError::Decode(anyhow::anyhow!(
-            "Unexpected response: {} {}",
-            response.status,
-            body.trim()
-        )))
+        if let Some(role) = self.role {
+            path.push_str(&format!("&sgrp={role}"));
+        }
+        Request::new(Method::GET, path)
+    }
+
+    pub async fn send(self, client: &impl HttpClient) -> Result<(), HttpError<Error>> {
+        let expected = format!("Modified account {}.", self.username);
+        let response = client
+            .execute(self.into_request())
+            .await
+            .map_err(HttpError::Transport)?;
+        parse_reply(response, &expected)
     }
 }
 
@@ -143,7 +236,10 @@ impl RemoveUserRequest {
     fn into_request(self) -> Request {
         Request::new(
             Method::GET,
-            format!("{PATH}?action=remove&user={}", self.username),
+            format!(
+                "{PATH}?action=remove&user={}",
+                encode_query_value(&self.username)
+            ),
         )
     }
 
@@ -153,29 +249,154 @@ impl RemoveUserRequest {
             .execute(self.into_request())
             .await
             .map_err(HttpError::Transport)?;
-        let body = response.body.map_err(|e| HttpError::Transport(e.into()))?;
-        let html_body = extract_body(&body).unwrap_or("");
-        let trimmed = html_body.trim();
-        if let Some(message) = trimmed.strip_prefix("Error: ") {
-            let message = message.strip_suffix('.').unwrap_or(message);
-            return Err(HttpError::Service(Error {
-                message: message.to_string(),
-            }));
+        parse_reply(response, &expected)
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct User {
+    pub name: String,
+    /// `None` if the user is in neither of the groups known to [`Group`].
+    pub group: Option<Group>,
+    /// `None` if the security groups of the user do not form one of the roles known to [`Role`].
+    pub role: Option<Role>,
+}
+
+/// The members of every group, e.g. `"viewer"`, as reported by the device.
+#[derive(Clone, Debug)]
+pub struct UsersData {
+    pub groups: BTreeMap<String, Vec<String>>,
+}
+
+impl UsersData {
+    fn is_member(&self, group: &str, user: &str) -> bool {
+        self.groups
+            .get(group)
+            .is_some_and(|members| members.iter().any(|m| m == user))
+    }
+
+    fn user(&self, name: &str) -> User {
+        let group = if self.is_member("root", name) {
+            Some(Group::Root)
+        } else if self.is_member("users", name) {
+            Some(Group::Users)
+        } else {
+            None
+        };
+        let role = Role::from_security_groups(
+            self.is_member("admin", name),
+            self.is_member("operator", name),
+            self.is_member("viewer", name),
+            self.is_member("ptz", name),
+        );
+        User {
+            name: name.to_string(),
+            group,
+            role,
         }
-        if response.status == StatusCode::OK && trimmed == expected {
-            return Ok(());
+    }
+
+    /// The users that can log in with digest authentication, i.e. all regular accounts.
+    pub fn users(&self) -> Vec<User> {
+        self.groups
+            .get("digusers")
+            .into_iter()
+            .flatten()
+            .map(|name| self.user(name))
+            .collect()
+    }
+
+    pub fn find(&self, name: &str) -> Option<User> {
+        self.is_member("digusers", name).then(|| self.user(name))
+    }
+}
+
+fn parse_users(text: &str) -> anyhow::Result<UsersData> {
+    let mut groups = BTreeMap::new();
+    for line in text.lines().map(str::trim).filter(|l| !l.is_empty()) {
+        let (group, members) = line
+            .split_once('=')
+            .with_context(|| format!("Expected group=\"members\" but got {line}"))?;
+        let members = members.trim_matches('"');
+        let members = members
+            .split(',')
+            .filter(|m| !m.is_empty())
+            .map(str::to_string)
+            .collect();
+        groups.insert(group.to_string(), members);
+    }
+    anyhow::ensure!(
+        groups.contains_key("digusers"),
+        "Expected a digusers group; text: {text}"
+    );
+    Ok(UsersData { groups })
+}
+
+/// List the users and the groups that they are members of.
+#[derive(Clone, Debug, Default)]
+pub struct GetUsersRequest;
+
+impl GetUsersRequest {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn into_request(self) -> Request {
+        Request::new(Method::GET, format!("{PATH}?action=get"))
+    }
+
+    pub async fn send(self, client: &impl HttpClient) -> Result<UsersData, HttpError<Error>> {
+        let response = client
+            .execute(self.into_request())
+            .await
+            .map_err(HttpError::Transport)?;
+        let status = response.status;
+        let body = response.body.map_err(|e| HttpError::Transport(e.into()))?;
+        if status != StatusCode::OK {
+            return Err(HttpError::Decode(anyhow::anyhow!(
+                "Unexpected response: {status} {}",
+                body.trim()
+            )));
         }
-        Err(HttpError::Decode(anyhow::anyhow!(
-            "Unexpected response: {} {}",
-            response.status,
-            body.trim()
-        )))
+        parse_users(&body).map_err(HttpError::Decode)
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::extract_body;
+    use super::*;
+
+    #[test]
+    fn can_parse_users() {
+        let data = parse_users(include_str!("pwdgrp/get_200.txt")).unwrap();
+        let users = data.users();
+        assert_eq!(users.len(), 3);
+        assert_eq!(
+            data.find("root").unwrap(),
+            User {
+                name: "root".to_string(),
+                group: Some(Group::Root),
+                role: Some(Role::AdminOperatorViewerPtz),
+            }
+        );
+        let operator = data.find("operator").unwrap();
+        assert_eq!(operator.group, Some(Group::Users));
+        assert_eq!(operator.role, Some(Role::OperatorViewer));
+        assert_eq!(data.find("viewer").unwrap().role, Some(Role::Viewer));
+        assert_eq!(data.find("nobody"), None);
+    }
+
+    #[test]
+    fn credentials_are_encoded() {
+        let request = UpdateUserRequest::new("root")
+            .password("p&ss=w rd")
+            .role(Role::AdminOperatorViewerPtz)
+            .into_request();
+        assert_eq!(
+            request.path,
+            "axis-cgi/pwdgrp.cgi?action=update&user=root&pwd=p%26ss%3Dw+rd&sgrp=admin:operator:viewer:ptz"
+        );
+    }
 
     #[test]
     fn extract_body_simple() {
@@ -0,0 +1,122 @@
+//! Bindings for the [server report API](https://developer.axis.com/vapix/device-configuration/server-report/).
+//!
+//! The text report is useful for a quick look at the state of a device, whereas the archives
+//! include the full logs and are what support usually asks for.
+use std::{
+    fmt::{Display, Formatter},
+    path::Path,
+};
+
+use anyhow::Context;
+use futures_util::StreamExt;
+use log::warn;
+use reqwest::Method;
+use tokio::{fs::File, io::AsyncWriteExt};
+
+use crate::{
+    http::{HttpClient, Request},
+    protocol_helpers::{http::Error as HttpError, text_http},
+    Client,
+};
+
+const PATH: &str = "axis-cgi/serverreport.cgi";
+
+/// Get the server report as plain text.
+#[derive(Clone, Debug, Default)]
+pub struct GetServerReportRequest;
+
+impl GetServerReportRequest {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn into_request(self) -> Request {
+        Request::new(Method::GET, format!("{PATH}?mode=text"))
+    }
+
+    pub async fn send(
+        self,
+        client: &(impl HttpClient + Sync),
+    ) -> Result<String, HttpError<std::convert::Infallible>> {
+        text_http::send_request(client, self.into_request()).await
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ArchiveMode {
+    /// A zip archive with the report and the logs.
+    Zip,
+    /// Like [`Self::Zip`] but also with a snapshot from each video channel.
+    ZipWithImage,
+    /// A tarball with everything needed to debug the device, sometimes called a debug archive.
+    TarAll,
+}
+
+impl Display for ArchiveMode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Zip => write!(f, "zip"),
+            Self::ZipWithImage => write!(f, "zip_with_image"),
+            Self::TarAll => write!(f, "tar_all"),
+        }
+    }
+}
+
+/// Download the server report as an archive.
+///
+/// Archives can be large and slow to generate, so they are streamed to disk rather than held in
+/// memory.
+#[derive(Clone, Debug)]
+pub struct DownloadServerReportRequest {
+    mode: ArchiveMode,
+}
+
+impl DownloadServerReportRequest {
+    pub fn new(mode: ArchiveMode) -> Self {
+        Self { mode }
+    }
+
+    /// Write the archive to `path`, returning the number of bytes written.
+    ///
+    /// If the download fails after `path` has been created, it is removed again.
+    // TODO: Migrate to `HttpClient` when cassette tests support binary responses.
+    pub async fn download(self, client: &Client, path: &Path) -> anyhow::Result<u64> {
+        let Self { mode } = self;
+        let response = client
+            .get(PATH)?
+            .query(&[("mode", mode.to_string())])
+            .send()
+            .await?
+            .error_for_status()?;
+
+        let file = File::create(path)
+            .await
+            .with_context(|| format!("Could not create {path:?}"))?;
+        let result = write_archive(response, file, path).await;
+        if result.is_err() {
+            if let Err(e) = tokio::fs::remove_file(path).await {
+                warn!("Could not remove partial server report {path:?}: {e}");
+            }
+        }
+        result
+    }
+}
+
+async fn write_archive(
+    response: reqwest::Response,
+    mut file: File,
+    path: &Path,
+) -> anyhow::Result<u64> {
+    let mut len = 0;
+    let mut stream = response.bytes_stream();
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.context("Failed to get server report")?;
+        file.write_all(&chunk)
+            .await
+            .with_context(|| format!("Could not write to {path:?}"))?;
+        len += u64::try_from(chunk.len())?;
+    }
+    file.flush().await?;
+    anyhow::ensure!(len != 0, "Server report is empty");
+    Ok(len)
+}
@@ -0,0 +1,143 @@
+//! Bindings for the [system log API](https://developer.axis.com/vapix/device-configuration/system-log/).
+use chrono::{DateTime, FixedOffset};
+use reqwest::Method;
+
+use crate::{
+    http::{HttpClient, Request},
+    protocol_helpers::{http::Error as HttpError, text_http},
+};
+
+const PATH: &str = "axis-cgi/systemlog.cgi";
+
+/// A line from the system log, e.g.
+/// `2024-03-01T09:06:18.123+01:00 axis-accc8e000000 [ INFO    ] systemd[1]: Started foo.`
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LogLine {
+    pub timestamp: Option<DateTime<FixedOffset>>,
+    pub hostname: Option<String>,
+    /// The severity, e.g. `"INFO"` or `"ERR"`.
+    pub level: Option<String>,
+    /// The process that logged the message, e.g. `"systemd[1]"`.
+    pub source: Option<String>,
+    pub message: String,
+    /// The line as it was received.
+    pub raw: String,
+}
+
+impl LogLine {
+    /// Parse a line, falling back on only the message if it is not in the expected format.
+    pub fn parse(line: &str) -> Self {
+        Self::try_parse(line).unwrap_or_else(|| Self {
+            timestamp: None,
+            hostname: None,
+            level: None,
+            source: None,
+            message: line.to_string(),
+            raw: line.to_string(),
+        })
+    }
+
+    fn try_parse(line: &str) -> Option<Self> {
+        let (timestamp, rest) = line.split_once(' ')?;
+        let timestamp = DateTime::parse_from_rfc3339(timestamp).ok()?;
+        let (hostname, rest) = rest.split_once(' ')?;
+        let (level, rest) = rest.strip_prefix('[')?.split_once(']')?;
+        let rest = rest.trim_start();
+        let (source, message) = match rest.split_once(": ") {
+            Some((source, message)) if !source.contains(' ') => (Some(source), message),
+            _ => (None, rest),
+        };
+        Some(Self {
+            timestamp: Some(timestamp),
+            hostname: Some(hostname.to_string()),
+            level: Some(level.trim().to_string()),
+            source: source.map(str::to_string),
+            message: message.to_string(),
+            raw: line.to_string(),
+        })
+    }
+
+    /// True if the line was logged with severity error or worse.
+    pub fn is_error(&self) -> bool {
+        matches!(
+            self.level.as_deref(),
+            Some("ERR" | "ERROR" | "CRIT" | "ALERT" | "EMERG")
+        )
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SystemLogData {
+    pub lines: Vec<LogLine>,
+}
+
+impl SystemLogData {
+    fn parse(text: &str) -> Self {
+        Self {
+            lines: text
+                .lines()
+                .filter(|l| !l.trim().is_empty())
+                .map(LogLine::parse)
+                .collect(),
+        }
+    }
+
+    /// The lines logged with severity error or worse.
+    pub fn errors(&self) -> impl Iterator<Item = &LogLine> {
+        self.lines.iter().filter(|l| l.is_error())
+    }
+}
+
+/// Get the system log.
+#[derive(Clone, Debug, Default)]
+pub struct GetSystemLogRequest;
+
+impl GetSystemLogRequest {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn into_request(self) -> Request {
+        Request::new(Method::GET, PATH.to_string())
+    }
+
+    pub async fn send(
+        self,
+        client: &(impl HttpClient + Sync),
+    ) -> Result<SystemLogData, HttpError<std::convert::Infallible>> {
+        let text = text_http::send_request(client, self.into_request()).await?;
+        Ok(SystemLogData::parse(&text))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_parse_system_log() {
+        let text = include_str!("system_log/get_200.txt");
+        let data = SystemLogData::parse(text);
+        assert_eq!(data.lines.len(), 4);
+
+        let first = data.lines.first().unwrap();
+        assert_eq!(
+            first.timestamp.unwrap().to_rfc3339(),
+            "2024-03-01T09:06:18.123+01:00"
+        );
+        assert_eq!(first.hostname.as_deref(), Some("axis-accc8e000000"));
+        assert_eq!(first.level.as_deref(), Some("INFO"));
+        assert_eq!(first.source.as_deref(), Some("systemd[1]"));
+        assert_eq!(first.message, "Started Network Time Synchronization.");
+
+        let errors = data.errors().collect::<Vec<_>>();
+        let [error] = errors.as_slice() else {
+            panic!("Expected one error but got {errors:#?}");
+        };
+        assert_eq!(error.source.as_deref(), Some("httpd[1234]"));
+
+        let last = data.lines.last().unwrap();
+        assert_eq!(last.timestamp, None);
+        assert_eq!(last.message, last.raw);
+    }
+}
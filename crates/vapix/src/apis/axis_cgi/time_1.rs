@@ -0,0 +1,152 @@
+//! The [Time API].
+//!
+//! [Time API]: https://developer.axis.com/vapix/network-video/time-api/
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize, Serializer};
+
+use crate::{
+    apis::api_discovery_1::ApiId,
+    http::HttpClient,
+    protocol_helpers::{http::Error, json_rpc, json_rpc_http},
+};
+
+pub const API_ID: ApiId = ApiId::new("time-service");
+
+const API_VERSION: &str = "1.0";
+
+const PATH: &str = "axis-cgi/time.cgi";
+
+/// Read the device clock and time zone.
+#[derive(Debug, Default)]
+pub struct GetDateTimeInfoRequest;
+
+impl GetDateTimeInfoRequest {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub async fn send(
+        self,
+        client: &(impl HttpClient + Sync),
+    ) -> Result<DateTimeInfoData, Error<json_rpc::Error>> {
+        let body = json_rpc::RequestBody::<()>::new(API_VERSION, "getDateTimeInfo", None);
+        json_rpc_http::send_request(client, PATH, &body).await
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DateTimeInfoData {
+    pub date_time: DateTime<Utc>,
+    pub dst_enabled: bool,
+    /// The local time including the UTC offset, e.g. `"2024-03-01T10:06:18+01:00"`.
+    pub local_date_time: String,
+    pub posix_time_zone: String,
+    /// The IANA time zone, e.g. `"Europe/Stockholm"`.
+    ///
+    /// Absent when the time zone is set as a POSIX string.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time_zone: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SetDateTimeParams {
+    #[serde(serialize_with = "serialize_date_time")]
+    date_time: DateTime<Utc>,
+}
+
+/// Serialize without fractional seconds, e.g. `2024-03-01T09:06:18Z`.
+fn serialize_date_time<S: Serializer>(
+    date_time: &DateTime<Utc>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&date_time.to_rfc3339_opts(SecondsFormat::Secs, true))
+}
+
+/// Set the device clock manually.
+///
+/// This has no lasting effect if the device synchronizes its clock with NTP.
+#[derive(Debug)]
+pub struct SetDateTimeRequest {
+    params: SetDateTimeParams,
+}
+
+impl SetDateTimeRequest {
+    pub fn new(date_time: DateTime<Utc>) -> Self {
+        Self {
+            params: SetDateTimeParams { date_time },
+        }
+    }
+
+    pub async fn send(
+        self,
+        client: &(impl HttpClient + Sync),
+    ) -> Result<SetDateTimeData, Error<json_rpc::Error>> {
+        let body = json_rpc::RequestBody::new(API_VERSION, "setDateTime", Some(self.params));
+        json_rpc_http::send_request(client, PATH, &body).await
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct SetDateTimeData {}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SetTimeZoneParams {
+    time_zone: String,
+}
+
+#[derive(Debug)]
+pub struct SetTimeZoneRequest {
+    params: SetTimeZoneParams,
+}
+
+impl SetTimeZoneRequest {
+    /// Use the IANA time zone `time_zone`, e.g. `"Europe/Stockholm"` or `"UTC"`.
+    pub fn new(time_zone: impl Into<String>) -> Self {
+        Self {
+            params: SetTimeZoneParams {
+                time_zone: time_zone.into(),
+            },
+        }
+    }
+
+    pub async fn send(
+        self,
+        client: &(impl HttpClient + Sync),
+    ) -> Result<SetTimeZoneData, Error<json_rpc::Error>> {
+        let body = json_rpc::RequestBody::new(API_VERSION, "setTimeZone", Some(self.params));
+        json_rpc_http::send_request(client, PATH, &body).await
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct SetTimeZoneData {}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[test]
+    fn set_date_time_omits_fractional_seconds() {
+        let date_time = Utc.with_ymd_and_hms(2024, 3, 1, 9, 6, 18).unwrap()
+            + chrono::Duration::milliseconds(123);
+        let body = json_rpc::RequestBody::new(
+            API_VERSION,
+            "setDateTime",
+            Some(SetDateTimeRequest::new(date_time).params),
+        );
+        assert_eq!(
+            serde_json::to_value(body).unwrap(),
+            serde_json::json!({
+                "apiVersion": "1.0",
+                "method": "setDateTime",
+                "params": {"dateTime": "2024-03-01T09:06:18Z"},
+            })
+        );
+    }
+}
@@ -0,0 +1,2 @@
+//! A collection of APIs that can be found under the `/axis-media/` path.
+pub mod rtsp;
@@ -3,3 +3,4 @@ pub mod recording_group_1;
 pub mod remote_object_storage_1_beta;
 pub mod siren_and_light_2_alpha;
 pub mod ssh_1;
+pub mod ssh_2;
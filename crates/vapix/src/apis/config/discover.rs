@@ -25,6 +25,15 @@ pub struct DiscoverData {
     pub device: DeviceInfo,
 }
 
+impl DiscoverData {
+    /// Check if the device implements `version` of `api`, e.g. `"v2"` of `"ssh"`.
+    pub fn supports(&self, api: &str, version: &str) -> bool {
+        self.apis
+            .get(api)
+            .is_some_and(|versions| versions.contains_key(version))
+    }
+}
+
 #[non_exhaustive]
 #[derive(Debug, Deserialize, Serialize)]
 pub struct DeviceInfo {
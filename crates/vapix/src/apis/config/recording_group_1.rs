@@ -1,88 +1,379 @@
 //! The [Recording group API].
 //!
 //! [Recording group API]: https://developer.axis.com/vapix/device-configuration/recording-group/
+use std::fmt::{Display, Formatter};
+
 use reqwest::Method;
 use serde::{Deserialize, Serialize};
-use serde_json::{json, Value};
+use serde_json::json;
 
 use crate::{
+    apis::remote_object_storage_1_beta::DestinationId,
     http::{HttpClient, Request},
     protocol_helpers::{http::Error, rest, rest_http},
 };
 
-#[derive(Debug, Deserialize, Serialize)]
-#[serde(rename_all = "camelCase")]
-pub struct CreateRecordingGroupResponse {
-    pub id: String,
-    pub container_format: String,
-    pub description: String,
-    pub destinations: Vec<Destination>,
-    pub max_retention_time: u64,
-    pub nice_name: String,
-    pub post_duration: u64,
-    pub pre_duration: u64,
-    pub segment_duration: SegmentDuration,
-    pub segment_size: SegmentSize,
-    pub span_duration: u64,
-    pub stream_options: String,
+const BASE_PATH: &str = "config/rest/recording-group/v2beta/recordingGroups";
+
+// Scalars
+
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub struct RecordingGroupId(String);
+
+impl RecordingGroupId {
+    pub fn new(id: String) -> Self {
+        Self(id)
+    }
+
+    pub fn into_string(self) -> String {
+        self.0
+    }
+
+    pub fn as_str(&self) -> &str {
+        self.0.as_str()
+    }
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+/// The container format that recordings are stored in.
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(from = "String", into = "String")]
+pub enum ContainerFormat {
+    Cmaf,
+    Matroska,
+    /// A format not known to this library.
+    Other(String),
+}
+
+impl From<String> for ContainerFormat {
+    fn from(s: String) -> Self {
+        match s.as_str() {
+            "cmaf" => Self::Cmaf,
+            "matroska" => Self::Matroska,
+            _ => Self::Other(s),
+        }
+    }
+}
+
+impl From<ContainerFormat> for String {
+    fn from(format: ContainerFormat) -> Self {
+        format.to_string()
+    }
+}
+
+impl Display for ContainerFormat {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Cmaf => write!(f, "cmaf"),
+            Self::Matroska => write!(f, "matroska"),
+            Self::Other(s) => write!(f, "{s}"),
+        }
+    }
+}
+
+// Objects (used by both requests and responses)
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Destination {
     pub remote_object_storage: RemoteObjectStorage,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+impl Destination {
+    /// Store recordings in the remote object storage destination `id`.
+    pub fn remote_object_storage(id: DestinationId) -> Self {
+        Self {
+            remote_object_storage: RemoteObjectStorage {
+                id,
+                prefix: String::new(),
+                postfix: String::new(),
+            },
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct RemoteObjectStorage {
-    pub id: String,
+    pub id: DestinationId,
     #[serde(default)]
     pub prefix: String,
     #[serde(default)]
     pub postfix: String,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 pub struct SegmentDuration {
     pub max: u64,
     pub target: u64,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 pub struct SegmentSize {
     pub max: u64,
     pub target: u64,
 }
 
+// Objects (used only by responses)
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordingGroupData {
+    pub id: RecordingGroupId,
+    pub container_format: ContainerFormat,
+    pub description: String,
+    pub destinations: Vec<Destination>,
+    pub max_retention_time: u64,
+    pub nice_name: String,
+    pub post_duration: u64,
+    pub pre_duration: u64,
+    pub segment_duration: SegmentDuration,
+    pub segment_size: SegmentSize,
+    pub span_duration: u64,
+    pub stream_options: String,
+}
+
+pub type CreateRecordingGroupResponse = RecordingGroupData;
+
+// Objects (used only by requests)
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CreateRecordingGroupData {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    nice_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    container_format: Option<ContainerFormat>,
+    destinations: Vec<Destination>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    max_retention_time: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    post_duration: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pre_duration: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    segment_duration: Option<SegmentDuration>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    segment_size: Option<SegmentSize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    span_duration: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    stream_options: Option<String>,
+}
+
+// Requests
+
+/// Create a recording group.
+///
+/// Properties that are not set are given default values by the device.
 #[derive(Debug, Default)]
 pub struct CreateRecordingGroupsRequest {
-    data: Value,
+    data: CreateRecordingGroupData,
 }
 
 impl CreateRecordingGroupsRequest {
     pub fn new() -> Self {
-        Self { data: Value::Null }
+        Self::default()
+    }
+
+    pub fn nice_name(mut self, nice_name: String) -> Self {
+        self.data.nice_name = Some(nice_name);
+        self
     }
 
-    pub fn data(mut self, data: Value) -> Self {
-        self.data = data;
+    pub fn description(mut self, description: String) -> Self {
+        self.data.description = Some(description);
         self
     }
 
+    pub fn container_format(mut self, container_format: ContainerFormat) -> Self {
+        self.data.container_format = Some(container_format);
+        self
+    }
+
+    /// Add a destination that recordings are stored in.
+    pub fn destination(mut self, destination: Destination) -> Self {
+        self.data.destinations.push(destination);
+        self
+    }
+
+    pub fn max_retention_time(mut self, max_retention_time: u64) -> Self {
+        self.data.max_retention_time = Some(max_retention_time);
+        self
+    }
+
+    pub fn pre_duration(mut self, pre_duration: u64) -> Self {
+        self.data.pre_duration = Some(pre_duration);
+        self
+    }
+
+    pub fn post_duration(mut self, post_duration: u64) -> Self {
+        self.data.post_duration = Some(post_duration);
+        self
+    }
+
+    pub fn segment_duration(mut self, target: u64, max: u64) -> Self {
+        self.data.segment_duration = Some(SegmentDuration { max, target });
+        self
+    }
+
+    pub fn segment_size(mut self, target: u64, max: u64) -> Self {
+        self.data.segment_size = Some(SegmentSize { max, target });
+        self
+    }
+
+    pub fn span_duration(mut self, span_duration: u64) -> Self {
+        self.data.span_duration = Some(span_duration);
+        self
+    }
+
+    pub fn stream_options(mut self, stream_options: String) -> Self {
+        self.data.stream_options = Some(stream_options);
+        self
+    }
+
+    pub fn into_request(self) -> Request {
+        // PANICS:
+        // The `unwrap` will never panic because `self.data` can always be serialized to JSON.
+        Request::new(Method::POST, BASE_PATH.to_string())
+            .json(serde_json::to_string_pretty(&json!({"data": self.data})).unwrap())
+    }
+
+    pub async fn send(
+        self,
+        client: &(impl HttpClient + Sync),
+    ) -> Result<RecordingGroupData, Error<rest::Error>> {
+        rest_http::send_request(client, self.into_request()).await
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct ListRecordingGroupsRequest;
+
+impl ListRecordingGroupsRequest {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn into_request(self) -> Request {
+        Request::new(Method::GET, BASE_PATH.to_string())
+    }
+
+    pub async fn send(
+        self,
+        client: &(impl HttpClient + Sync),
+    ) -> Result<Vec<RecordingGroupData>, Error<rest::Error>> {
+        rest_http::send_request(client, self.into_request()).await
+    }
+}
+
+#[derive(Debug)]
+pub struct GetRecordingGroupRequest {
+    id: RecordingGroupId,
+}
+
+impl GetRecordingGroupRequest {
+    pub fn new(id: RecordingGroupId) -> Self {
+        Self { id }
+    }
+
     pub fn into_request(self) -> Request {
-        let body = serde_json::to_string_pretty(&json!({"data": self.data})).unwrap();
         Request::new(
-            Method::POST,
-            "config/rest/recording-group/v2beta/recordingGroups".to_string(),
+            Method::GET,
+            format!("{BASE_PATH}/{}", self.id.into_string()),
         )
-        .json(body)
     }
 
     pub async fn send(
         self,
         client: &(impl HttpClient + Sync),
-    ) -> Result<CreateRecordingGroupResponse, Error<rest::Error>> {
+    ) -> Result<RecordingGroupData, Error<rest::Error>> {
+        rest_http::send_request(client, self.into_request()).await
+    }
+}
+
+/// Update a single property of a recording group.
+#[derive(Debug)]
+pub struct UpdateRecordingGroupRequest {
+    id: RecordingGroupId,
+    property: String,
+    data: serde_json::Value,
+}
+
+impl UpdateRecordingGroupRequest {
+    fn property(id: RecordingGroupId, property: &str, data: serde_json::Value) -> Self {
+        Self {
+            id,
+            property: property.to_string(),
+            data,
+        }
+    }
+
+    pub fn nice_name(id: RecordingGroupId, nice_name: String) -> Self {
+        Self::property(id, "niceName", serde_json::Value::String(nice_name))
+    }
+
+    pub fn description(id: RecordingGroupId, description: String) -> Self {
+        Self::property(id, "description", serde_json::Value::String(description))
+    }
+
+    pub fn destinations(id: RecordingGroupId, destinations: Vec<Destination>) -> Self {
+        // PANICS:
+        // The `unwrap` will never panic because destinations can always be serialized to JSON.
+        Self::property(
+            id,
+            "destinations",
+            serde_json::to_value(destinations).unwrap(),
+        )
+    }
+
+    pub fn max_retention_time(id: RecordingGroupId, max_retention_time: u64) -> Self {
+        Self::property(id, "maxRetentionTime", json!(max_retention_time))
+    }
+
+    pub fn pre_duration(id: RecordingGroupId, pre_duration: u64) -> Self {
+        Self::property(id, "preDuration", json!(pre_duration))
+    }
+
+    pub fn post_duration(id: RecordingGroupId, post_duration: u64) -> Self {
+        Self::property(id, "postDuration", json!(post_duration))
+    }
+
+    pub fn into_request(self) -> Request {
+        // PANICS:
+        // The `unwrap` will never panic because `self.data` is a `serde_json::Value` which can
+        // always be serialized to JSON.
+        Request::new(
+            Method::PATCH,
+            format!("{BASE_PATH}/{}/{}", self.id.into_string(), self.property),
+        )
+        .json(serde_json::to_string_pretty(&json!({"data": self.data})).unwrap())
+    }
+
+    pub async fn send(self, client: &(impl HttpClient + Sync)) -> Result<(), Error<rest::Error>> {
+        rest_http::send_request(client, self.into_request()).await
+    }
+}
+
+#[derive(Debug)]
+pub struct DeleteRecordingGroupRequest {
+    id: RecordingGroupId,
+}
+
+impl DeleteRecordingGroupRequest {
+    pub fn new(id: RecordingGroupId) -> Self {
+        Self { id }
+    }
+
+    pub fn into_request(self) -> Request {
+        Request::new(
+            Method::DELETE,
+            format!("{BASE_PATH}/{}", self.id.into_string()),
+        )
+    }
+
+    pub async fn send(self, client: &(impl HttpClient + Sync)) -> Result<(), Error<rest::Error>> {
         rest_http::send_request(client, self.into_request()).await
     }
 }
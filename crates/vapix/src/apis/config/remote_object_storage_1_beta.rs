@@ -76,6 +76,39 @@ pub struct S3Destination {
     pub session_token: Option<String>,
 }
 
+impl S3Destination {
+    /// A destination in `bucket` of the S3-compatible store at `url`, e.g. a MinIO server.
+    pub fn new(bucket: String, url: Url, access_key_id: String, secret_access_key: String) -> Self {
+        Self {
+            bucket,
+            region: None,
+            url: url.to_string(),
+            access_key_id: Some(access_key_id),
+            secret_access_key: Some(secret_access_key),
+            session_token: None,
+        }
+    }
+
+    pub fn region(mut self, region: String) -> Self {
+        self.region = Some(region);
+        self
+    }
+
+    /// Use temporary credentials.
+    pub fn session_token(mut self, session_token: String) -> Self {
+        self.session_token = Some(session_token);
+        self
+    }
+}
+
+/// The kind of store that a destination refers to, and how to access it.
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DestinationKind {
+    Azure(AzureDestination),
+    S3(S3Destination),
+}
+
 // Objects (used only by responses)
 
 #[derive(Clone, Debug, PartialEq, serde::Deserialize, serde::Serialize)]
@@ -84,10 +117,24 @@ pub struct DestinationData {
     pub id: DestinationId,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
-    #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub azure: Option<AzureDestination>,
-    #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub s3: Option<S3Destination>,
+    #[serde(flatten)]
+    pub kind: DestinationKind,
+}
+
+impl DestinationData {
+    pub fn azure(&self) -> Option<&AzureDestination> {
+        match &self.kind {
+            DestinationKind::Azure(azure) => Some(azure),
+            DestinationKind::S3(_) => None,
+        }
+    }
+
+    pub fn s3(&self) -> Option<&S3Destination> {
+        match &self.kind {
+            DestinationKind::Azure(_) => None,
+            DestinationKind::S3(s3) => Some(s3),
+        }
+    }
 }
 
 // Objects (used only by requests)
@@ -96,10 +143,8 @@ pub struct DestinationData {
 #[serde(rename_all = "camelCase")]
 pub struct CreateDestinationData {
     id: DestinationId,
-    #[serde(default, skip_serializing_if = "Option::is_none")]
-    azure: Option<AzureDestination>,
-    #[serde(default, skip_serializing_if = "Option::is_none")]
-    s3: Option<S3Destination>,
+    #[serde(flatten)]
+    kind: DestinationKind,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     description: Option<String>,
 }
@@ -117,26 +162,22 @@ impl CreateDestinationRequest {
         self
     }
 
-    pub fn azure(id: DestinationId, azure: AzureDestination) -> Self {
+    pub fn new(id: DestinationId, kind: DestinationKind) -> Self {
         Self {
             data: CreateDestinationData {
                 id,
-                azure: Some(azure),
-                s3: None,
+                kind,
                 description: None,
             },
         }
     }
 
+    pub fn azure(id: DestinationId, azure: AzureDestination) -> Self {
+        Self::new(id, DestinationKind::Azure(azure))
+    }
+
     pub fn s3(id: DestinationId, s3: S3Destination) -> Self {
-        Self {
-            data: CreateDestinationData {
-                id,
-                azure: None,
-                s3: Some(s3),
-                description: None,
-            },
-        }
+        Self::new(id, DestinationKind::S3(s3))
     }
 
     pub fn into_request(self) -> Request {
@@ -174,6 +215,31 @@ impl ListDestinationsRequest {
     }
 }
 
+#[derive(Debug)]
+pub struct GetDestinationRequest {
+    id: DestinationId,
+}
+
+impl GetDestinationRequest {
+    pub fn new(id: DestinationId) -> Self {
+        Self { id }
+    }
+
+    pub fn into_request(self) -> Request {
+        Request::new(
+            Method::GET,
+            format!("{BASE_PATH}/{}", self.id.into_string()),
+        )
+    }
+
+    pub async fn send(
+        self,
+        client: &(impl HttpClient + Sync),
+    ) -> Result<DestinationData, Error<rest::Error>> {
+        rest_http::send_request(client, self.into_request()).await
+    }
+}
+
 #[derive(Debug)]
 pub struct UpdateDestinationRequest {
     id: DestinationId,
@@ -190,6 +256,17 @@ impl UpdateDestinationRequest {
         }
     }
 
+    pub fn s3(id: DestinationId, s3: S3Destination) -> Self {
+        Self {
+            id,
+            property: "s3".to_string(),
+            // PANICS:
+            // The `unwrap` will never panic because `S3Destination` has only string fields and
+            // can always be serialized to JSON.
+            data: serde_json::to_value(s3).unwrap(),
+        }
+    }
+
     pub fn description(id: DestinationId, description: String) -> Self {
         Self {
             id,
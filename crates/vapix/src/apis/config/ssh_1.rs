@@ -1,9 +1,7 @@
 //! The SSH v1 API.
 //!
-//! Note that there is also a [v2].
+//! Note that there is also a [v2](super::ssh_2).
 //! However, v1 may still be the only API that can be used to manage SSH users on 11.x.
-//!
-//! [v2]: https://developer.axis.com/vapix/device-configuration/ssh-management/
 use reqwest::Method;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
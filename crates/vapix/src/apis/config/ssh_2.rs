@@ -0,0 +1,310 @@
+//! The [SSH management] v2 API.
+//!
+//! Unlike [v1](super::ssh_1), this version can list users, authorize public keys and configure
+//! the SSH server.
+//! Use [`DiscoverData::supports`](super::discover::DiscoverData::supports) with `"ssh"` and
+//! `"v2"` to determine if it is available on a device.
+//!
+//! [SSH management]: https://developer.axis.com/vapix/device-configuration/ssh-management/
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use url::form_urlencoded::byte_serialize;
+
+use crate::{
+    http::{HttpClient, Request},
+    protocol_helpers::{http::Error, rest, rest_http},
+};
+
+const BASE_PATH: &str = "config/rest/ssh/v2";
+
+/// Encode `username` for use as a path segment.
+fn user_path(username: &str) -> String {
+    // Literal `+` is encoded, so any `+` in the output is a space, which is not decoded as such
+    // in a path.
+    let encoded: String = byte_serialize(username.as_bytes()).collect();
+    format!("{BASE_PATH}/users/{}", encoded.replace('+', "%20"))
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct User {
+    pub username: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+    /// Public keys, in the format of an OpenSSH `authorized_keys` line, that may be used to log
+    /// in as this user.
+    #[serde(default)]
+    pub authorized_keys: Vec<String>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerConfig {
+    pub enabled: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub port: Option<u16>,
+    /// Whether users may log in with a password rather than only with a key.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub password_authentication: Option<bool>,
+}
+
+#[derive(Debug, Default)]
+pub struct ListUsersRequest;
+
+impl ListUsersRequest {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn into_request(self) -> Request {
+        Request::new(Method::GET, format!("{BASE_PATH}/users"))
+    }
+
+    pub async fn send(
+        self,
+        client: &(impl HttpClient + Sync),
+    ) -> Result<Vec<User>, Error<rest::Error>> {
+        rest_http::send_request(client, self.into_request()).await
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AddUserData {
+    username: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    password: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    comment: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    authorized_keys: Vec<String>,
+}
+
+#[derive(Debug)]
+pub struct AddUserRequest {
+    data: AddUserData,
+}
+
+impl AddUserRequest {
+    /// Creates a new user.
+    ///
+    /// The user cannot log in until a password or an authorized key is added.
+    ///
+    /// # Arguments
+    ///
+    /// - `username` no shorter than 1, no longer than 32 and matching `^[a-z_][a-z0-9-_]*[$]?$`.
+    pub fn new(username: impl ToString) -> Self {
+        Self {
+            data: AddUserData {
+                username: username.to_string(),
+                password: None,
+                comment: None,
+                authorized_keys: Vec::new(),
+            },
+        }
+    }
+
+    /// Sets the password of the SSH user.
+    ///
+    /// Must be no shorter than 1 and no longer than 256.
+    pub fn password(mut self, password: impl ToString) -> Self {
+        self.data.password = Some(password.to_string());
+        self
+    }
+
+    /// Sets the full name or the comment of the SSH user.
+    ///
+    /// Must be no longer than 256 and must match `^[^:\n]*$`.
+    pub fn comment(mut self, comment: impl ToString) -> Self {
+        self.data.comment = Some(comment.to_string());
+        self
+    }
+
+    /// Authorizes a public key, e.g. the contents of `~/.ssh/id_ed25519.pub`.
+    pub fn authorized_key(mut self, key: impl ToString) -> Self {
+        self.data
+            .authorized_keys
+            .push(key.to_string().trim().to_string());
+        self
+    }
+
+    pub fn into_request(self) -> Request {
+        // PANICS:
+        // The `unwrap` will never panic because `self.data` can always be serialized to JSON.
+        let body = serde_json::to_string_pretty(&json!({"data": self.data})).unwrap();
+        Request::new(Method::POST, format!("{BASE_PATH}/users")).json(body)
+    }
+
+    pub async fn send(self, client: &(impl HttpClient + Sync)) -> Result<(), Error<rest::Error>> {
+        rest_http::send_request(client, self.into_request()).await
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SetUserData {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    password: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    comment: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    authorized_keys: Option<Vec<String>>,
+}
+
+#[derive(Debug)]
+pub struct SetUserRequest {
+    username: String,
+    data: SetUserData,
+}
+
+impl SetUserRequest {
+    /// Updates an existing user.
+    pub fn new(username: impl ToString) -> Self {
+        Self {
+            username: username.to_string(),
+            data: SetUserData {
+                password: None,
+                comment: None,
+                authorized_keys: None,
+            },
+        }
+    }
+
+    pub fn password(mut self, password: impl ToString) -> Self {
+        self.data.password = Some(password.to_string());
+        self
+    }
+
+    pub fn comment(mut self, comment: impl ToString) -> Self {
+        self.data.comment = Some(comment.to_string());
+        self
+    }
+
+    /// Replaces the authorized keys of the user.
+    pub fn authorized_keys(mut self, keys: Vec<String>) -> Self {
+        self.data.authorized_keys = Some(keys);
+        self
+    }
+
+    pub fn into_request(self) -> Request {
+        let Self { username, data } = self;
+        // PANICS:
+        // The `unwrap` will never panic because `data` can always be serialized to JSON.
+        let body = serde_json::to_string_pretty(&json!({"data": data})).unwrap();
+        Request::new(Method::PATCH, user_path(&username)).json(body)
+    }
+
+    pub async fn send(self, client: &(impl HttpClient + Sync)) -> Result<(), Error<rest::Error>> {
+        rest_http::send_request(client, self.into_request()).await
+    }
+}
+
+#[derive(Debug)]
+pub struct DeleteUserRequest {
+    username: String,
+}
+
+impl DeleteUserRequest {
+    pub fn new(username: impl ToString) -> Self {
+        Self {
+            username: username.to_string(),
+        }
+    }
+
+    pub fn into_request(self) -> Request {
+        Request::new(Method::DELETE, user_path(&self.username))
+    }
+
+    pub async fn send(self, client: &(impl HttpClient + Sync)) -> Result<(), Error<rest::Error>> {
+        rest_http::send_request(client, self.into_request()).await
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct GetServerConfigRequest;
+
+impl GetServerConfigRequest {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn into_request(self) -> Request {
+        Request::new(Method::GET, format!("{BASE_PATH}/server"))
+    }
+
+    pub async fn send(
+        self,
+        client: &(impl HttpClient + Sync),
+    ) -> Result<ServerConfig, Error<rest::Error>> {
+        rest_http::send_request(client, self.into_request()).await
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SetServerConfigData {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    port: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    password_authentication: Option<bool>,
+}
+
+/// Updates the SSH server configuration.
+///
+/// Properties that are not set are left unchanged.
+#[derive(Debug, Default)]
+pub struct SetServerConfigRequest {
+    data: SetServerConfigData,
+}
+
+impl SetServerConfigRequest {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.data.enabled = Some(enabled);
+        self
+    }
+
+    pub fn port(mut self, port: u16) -> Self {
+        self.data.port = Some(port);
+        self
+    }
+
+    pub fn password_authentication(mut self, allowed: bool) -> Self {
+        self.data.password_authentication = Some(allowed);
+        self
+    }
+
+    pub fn into_request(self) -> Request {
+        // PANICS:
+        // The `unwrap` will never panic because `self.data` can always be serialized to JSON.
+        let body = serde_json::to_string_pretty(&json!({"data": self.data})).unwrap();
+        Request::new(Method::PATCH, format!("{BASE_PATH}/server")).json(body)
+    }
+
+    pub async fn send(self, client: &(impl HttpClient + Sync)) -> Result<(), Error<rest::Error>> {
+        rest_http::send_request(client, self.into_request()).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn username_is_encoded_in_path() {
+        assert_eq!(
+            DeleteUserRequest::new("a/b c+d").into_request().path,
+            "config/rest/ssh/v2/users/a%2Fb%20c%2Bd"
+        );
+        assert_eq!(
+            SetUserRequest::new("../server").into_request().path,
+            "config/rest/ssh/v2/users/..%2Fserver"
+        );
+    }
+}
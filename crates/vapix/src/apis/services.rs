@@ -1,2 +1,3 @@
 pub mod action1;
+pub mod cert;
 pub mod event1;
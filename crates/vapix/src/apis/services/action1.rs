@@ -3,12 +3,28 @@
 //! [action service API]: https://developer.axis.com/vapix/network-video/event-and-action-services
 mod action_configurations;
 mod action_rules;
+mod condition;
+mod recipient_configurations;
+mod templates;
 
 pub use action_configurations::{
-    AddActionConfigurationRequest, AddActionConfigurationResponse, GetActionConfigurationsRequest,
-    GetActionConfigurationsResponse, RemoveActionConfigurationRequest,
+    ActionConfiguration, ActionConfigurations, AddActionConfigurationRequest,
+    AddActionConfigurationResponse, GetActionConfigurationsRequest,
+    GetActionConfigurationsResponse, Parameter, Parameters, RemoveActionConfigurationRequest,
 };
 pub use action_rules::{
     AddActionRuleRequest, AddActionRuleResponse, Condition, GetActionRulesRequest,
-    GetActionRulesResponse, RemoveActionRuleRequest,
+    GetActionRulesResponse, RemoveActionRuleRequest, ReplaceActionRuleError,
+    ReplaceActionRuleRequest,
+};
+pub use condition::{ConditionBuilder, ConditionError};
+pub use recipient_configurations::{
+    AddRecipientConfigurationRequest, AddRecipientConfigurationResponse,
+    GetRecipientConfigurationsRequest, GetRecipientConfigurationsResponse, RecipientConfiguration,
+    RecipientConfigurations, RemoveRecipientConfigurationRequest,
+};
+pub use templates::{
+    ActionTemplate, ActionTemplates, GetActionTemplatesRequest, GetActionTemplatesResponse,
+    GetRecipientTemplatesRequest, GetRecipientTemplatesResponse, RecipientTemplate,
+    RecipientTemplates,
 };
@@ -1,9 +1,10 @@
 use std::convert::Infallible;
 
-use anyhow::Context;
+use anyhow::{bail, Context};
 use serde::{de::IgnoredAny, Deserialize, Serialize};
 
 use crate::{
+    apis::action1::ActionTemplate,
     http::{HttpClient, Request},
     protocol_helpers::{http::Error, soap, soap_http, soap_http::SoapResponse},
 };
@@ -23,17 +24,26 @@ pub struct AddActionConfigurationRequest {
     name: Option<String>,
     template_token: String,
     parameters: Parameters,
+    expected: Option<Vec<String>>,
 }
 
-// TODO: Consider enabling typed templates to give users early feedback about missing params
 impl AddActionConfigurationRequest {
     pub fn new(template_token: &str) -> AddActionConfigurationRequest {
         Self {
             name: None,
             template_token: template_token.to_string(),
-            parameters: Parameters {
-                parameter: Vec::new(),
-            },
+            parameters: Parameters::default(),
+            expected: None,
+        }
+    }
+
+    /// Like [`Self::new`] but the parameters are checked against the template before sending.
+    ///
+    /// This gives early feedback instead of a `ParametersMissmatchFault` from the device.
+    pub fn from_template(template: &ActionTemplate) -> AddActionConfigurationRequest {
+        Self {
+            expected: Some(template.parameters.names()),
+            ..Self::new(&template.template_token)
         }
     }
 
@@ -55,7 +65,11 @@ impl AddActionConfigurationRequest {
             name,
             template_token,
             parameters,
+            expected,
         } = self;
+        if let Some(expected) = expected {
+            parameters.check(&template_token, &expected)?;
+        }
         let mut params = String::new();
         params.push_str(r#"<NewActionConfiguration>"#);
         if let Some(name) = name {
@@ -161,18 +175,37 @@ pub struct ActionConfiguration {
     pub parameters: Parameters,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Default, Deserialize, Serialize)]
 pub struct Parameters {
-    #[serde(rename = "Parameter")]
+    #[serde(default, rename = "Parameter")]
     pub parameter: Vec<Parameter>,
 }
 
+impl Parameters {
+    pub(super) fn names(&self) -> Vec<String> {
+        self.parameter.iter().map(|p| p.name.clone()).collect()
+    }
+
+    /// Check that exactly the `expected` parameters are set.
+    pub(super) fn check(&self, template_token: &str, expected: &[String]) -> anyhow::Result<()> {
+        let actual = self.names();
+        if let Some(name) = actual.iter().find(|n| !expected.contains(n)) {
+            bail!("Template {template_token} has no parameter {name}");
+        }
+        if let Some(name) = expected.iter().find(|n| !actual.contains(n)) {
+            bail!("Template {template_token} requires parameter {name}");
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 pub struct Parameter {
     #[serde(rename = "@Name")]
     pub name: String,
 
-    #[serde(rename = "@Value")]
+    /// The value of the parameter; empty in templates.
+    #[serde(default, rename = "@Value")]
     pub value: String,
 }
 
@@ -1,6 +1,7 @@
 use std::convert::Infallible;
 
 use anyhow::Context;
+use quick_xml::escape::partial_escape;
 use serde::{de::IgnoredAny, Deserialize};
 
 use crate::{
@@ -12,6 +13,11 @@ const PATH: &str = "vapix/services";
 
 const NAMESPACE: &str = "http://www.axis.com/vapix/ws/action1";
 
+/// Add an action rule.
+///
+/// The name and the conditions are XML escaped when the request is built, so rules fetched using
+/// [`GetActionRulesRequest`] can be passed back as is.
+#[derive(Debug)]
 pub struct AddActionRuleRequest {
     pub name: String,
     pub enabled: bool,
@@ -51,7 +57,7 @@ impl AddActionRuleRequest {
         let mut params = String::new();
         params.push_str(r#"<NewActionRule xmlns:tns1="http://www.onvif.org/ver10/topics" xmlns:tnsaxis="http://www.axis.com/2009/event/topics">"#);
         params.push_str(r#"<Name>"#);
-        params.push_str(&name);
+        params.push_str(&partial_escape(&name));
         params.push_str(r#"</Name>"#);
         params.push_str(r#"<Enabled>"#);
         params.push_str(&enabled.to_string());
@@ -64,10 +70,10 @@ impl AddActionRuleRequest {
             } = condition;
             params.push_str(r#"<Condition>"#);
             params.push_str(r#"<TopicExpression Dialect="http://docs.oasis-open.org/wsn/t-1/TopicExpression/Concrete" xmlns="http://docs.oasis-open.org/wsn/b-2">"#);
-            params.push_str(&topic_expression);
+            params.push_str(&partial_escape(&topic_expression));
             params.push_str(r#"</TopicExpression>"#);
             params.push_str(r#"<MessageContent Dialect="http://www.onvif.org/ver10/tev/messageContentFilter/ItemFilter" xmlns="http://docs.oasis-open.org/wsn/b-2">"#);
-            params.push_str(&message_content);
+            params.push_str(&partial_escape(&message_content));
             params.push_str(r#"</MessageContent>"#);
             params.push_str(r#"</Condition>"#);
         }
@@ -89,6 +95,58 @@ impl AddActionRuleRequest {
     }
 }
 
+/// Replace an action rule with a new definition.
+///
+/// The action service has no operation for modifying a rule, so the new rule is added before the
+/// old one is removed. Consequently, the replacement gets a new ID and, if removing the old rule
+/// fails, both rules will exist; the error then carries the new ID so that the caller can clean up.
+#[derive(Debug)]
+pub struct ReplaceActionRuleRequest {
+    rule_id: u16,
+    rule: AddActionRuleRequest,
+}
+
+impl ReplaceActionRuleRequest {
+    pub fn new(rule_id: u16, rule: AddActionRuleRequest) -> Self {
+        Self { rule_id, rule }
+    }
+
+    pub async fn send(
+        self,
+        client: &(impl HttpClient + Sync),
+    ) -> Result<AddActionRuleResponse, ReplaceActionRuleError> {
+        let Self { rule_id, rule } = self;
+        let response = rule
+            .send(client)
+            .await
+            .map_err(ReplaceActionRuleError::Add)?;
+        RemoveActionRuleRequest::new(rule_id)
+            .send(client)
+            .await
+            .map_err(|source| ReplaceActionRuleError::Remove {
+                old_rule_id: rule_id,
+                new_rule_id: response.id,
+                source,
+            })?;
+        Ok(response)
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ReplaceActionRuleError {
+    /// The new rule could not be added, so the old rule is unchanged.
+    #[error("Could not add the replacement rule")]
+    Add(#[source] Error<Infallible>),
+    /// The new rule was added but the old rule could not be removed, so both rules exist.
+    #[error("Added rule {new_rule_id} but could not remove rule {old_rule_id}")]
+    Remove {
+        old_rule_id: u16,
+        new_rule_id: u16,
+        #[source]
+        source: Error<Infallible>,
+    },
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "PascalCase")]
 pub struct AddActionRuleResponse {
@@ -145,10 +203,15 @@ impl SoapResponse for RemoveActionRuleResponse {
     }
 }
 
+/// A filter on the events that trigger a rule.
+///
+/// Prefer [`Condition::builder`] over writing the fields by hand.
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "PascalCase")]
 pub struct Condition {
     pub topic_expression: String,
+    /// An XPath expression.
+    #[serde(default)]
     pub message_content: String,
 }
 
@@ -207,10 +270,120 @@ impl GetActionRulesRequest {
 
 #[cfg(test)]
 mod tests {
+    use std::{collections::VecDeque, sync::Mutex};
+
     use expect_test::expect;
+    use reqwest::StatusCode;
 
-    use super::{AddActionRuleResponse, GetActionRulesResponse};
-    use crate::protocol_helpers::soap::parse_soap;
+    use super::*;
+    use crate::{
+        http::{Body, Response},
+        protocol_helpers::soap::parse_soap,
+    };
+
+    /// Responds to requests in order and records the request bodies.
+    struct FakeClient {
+        responses: Mutex<VecDeque<(StatusCode, &'static str)>>,
+        requests: Mutex<Vec<String>>,
+    }
+
+    impl FakeClient {
+        fn new(responses: Vec<(StatusCode, &'static str)>) -> Self {
+            Self {
+                responses: Mutex::new(responses.into()),
+                requests: Mutex::new(Vec::new()),
+            }
+        }
+    }
+
+    impl HttpClient for FakeClient {
+        async fn execute(&self, request: Request) -> Result<Response, anyhow::Error> {
+            let Some(Body::Bytes(body)) = request.body else {
+                panic!("Expected a buffered body");
+            };
+            self.requests
+                .lock()
+                .unwrap()
+                .push(String::from_utf8(body).unwrap());
+            let (status, body) = self.responses.lock().unwrap().pop_front().unwrap();
+            Ok(Response {
+                status,
+                body: Ok(body.to_string()),
+            })
+        }
+    }
+
+    const ADDED: (StatusCode, &str) = (
+        StatusCode::OK,
+        include_str!("examples/add_action_rule_200_response.xml"),
+    );
+
+    const REMOVED: (StatusCode, &str) = (
+        StatusCode::OK,
+        include_str!("examples/remove_action_rule_200_response.xml"),
+    );
+
+    const FAULT: (StatusCode, &str) = (
+        StatusCode::BAD_REQUEST,
+        include_str!("examples/add_action_rule_400_response.xml"),
+    );
+
+    fn replace_request() -> ReplaceActionRuleRequest {
+        ReplaceActionRuleRequest::new(7, AddActionRuleRequest::new("rule".to_string(), 2))
+    }
+
+    #[tokio::test]
+    async fn replace_action_rule_adds_before_removing() {
+        let client = FakeClient::new(vec![ADDED, REMOVED]);
+        let response = replace_request().send(&client).await.unwrap();
+        assert_eq!(response.id, 1);
+
+        let requests = client.requests.lock().unwrap();
+        let [add, remove] = requests.as_slice() else {
+            panic!("Expected two requests but got {requests:#?}");
+        };
+        assert!(add.contains("<Name>rule</Name>"));
+        assert!(remove.contains("<RuleID>7</RuleID>"));
+    }
+
+    #[tokio::test]
+    async fn replace_action_rule_keeps_old_rule_if_add_fails() {
+        let client = FakeClient::new(vec![FAULT]);
+        let error = replace_request().send(&client).await.unwrap_err();
+        assert!(matches!(
+            error,
+            ReplaceActionRuleError::Add(Error::Decode(_))
+        ));
+        assert_eq!(client.requests.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn replace_action_rule_reports_new_rule_if_remove_fails() {
+        let client = FakeClient::new(vec![ADDED, FAULT]);
+        let error = replace_request().send(&client).await.unwrap_err();
+        let ReplaceActionRuleError::Remove {
+            old_rule_id,
+            new_rule_id,
+            source: Error::Decode(_),
+        } = error
+        else {
+            panic!("Expected a remove error but got {error:?}");
+        };
+        assert_eq!((old_rule_id, new_rule_id), (7, 1));
+    }
+
+    #[test]
+    fn add_action_rule_request_escapes_name_and_conditions() {
+        let envelope = AddActionRuleRequest::new("Motion & <sound>".to_string(), 1)
+            .condition(Condition {
+                topic_expression: "tns1:Device/tnsaxis:IO/Port".to_string(),
+                message_content: r#"boolean(//SimpleItem[@Name="port" and @Value<"3"])"#
+                    .to_string(),
+            })
+            .into_envelope();
+        assert!(envelope.contains("<Name>Motion &amp; &lt;sound&gt;</Name>"));
+        assert!(envelope.contains(r#"@Value&lt;"3"])</MessageContent>"#));
+    }
 
     #[test]
     fn can_deserialize_add_action_rule_200_response() {
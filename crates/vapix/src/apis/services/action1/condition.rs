@@ -0,0 +1,229 @@
+use crate::apis::{
+    action1::Condition,
+    event1::{MessageInstance, SimpleItemInstance},
+};
+
+/// Namespace prefixes declared on the `NewActionRule` element.
+const KNOWN_PREFIXES: [&str; 2] = ["tns1", "tnsaxis"];
+
+#[derive(Debug, thiserror::Error)]
+pub enum ConditionError {
+    #[error("Topic {topic_expression} uses the undeclared namespace prefix {prefix}")]
+    UnknownPrefix {
+        topic_expression: String,
+        prefix: String,
+    },
+    #[error("Topic {topic_expression} has no simple item named {name}")]
+    UnknownSimpleItem {
+        topic_expression: String,
+        name: String,
+    },
+    #[error("Topic {topic_expression} is not a property event")]
+    NoPropertyState { topic_expression: String },
+    #[error("Simple item {name} of type {value_type} cannot take the value {value}")]
+    InvalidValue {
+        name: String,
+        value_type: String,
+        value: String,
+    },
+    #[error("Value {0} contains both single and double quotes")]
+    UnquotableValue(String),
+    #[error("Topic {topic_expression} has no filter; add a simple item or a state")]
+    NoFilter { topic_expression: String },
+}
+
+impl Condition {
+    /// Start building a condition that matches the event described by `instance`.
+    pub fn builder(instance: &MessageInstance) -> ConditionBuilder<'_> {
+        ConditionBuilder {
+            instance,
+            items: Vec::new(),
+        }
+    }
+}
+
+/// Builder for a [`Condition`] that is validated against an event instance.
+///
+/// Obtain the instance from [`GetEventInstancesRequest`](crate::apis::event1::GetEventInstancesRequest).
+#[derive(Debug)]
+pub struct ConditionBuilder<'a> {
+    instance: &'a MessageInstance,
+    items: Vec<(String, String)>,
+}
+
+impl ConditionBuilder<'_> {
+    /// Match only events where the simple item `name` has the value `value`.
+    ///
+    /// The item may be declared in the source, key or data of the message.
+    pub fn simple_item(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.items.push((name.into(), value.into()));
+        self
+    }
+
+    /// Match only events where the property state has the value `value`.
+    ///
+    /// For most property events the state is a boolean such as `active` or `ready`.
+    pub fn state(self, value: bool) -> Result<Self, ConditionError> {
+        let name = self
+            .instance
+            .property_state()
+            .ok_or_else(|| ConditionError::NoPropertyState {
+                topic_expression: self.instance.topic_expression.clone(),
+            })?
+            .name
+            .clone();
+        Ok(self.simple_item(name, if value { "1" } else { "0" }))
+    }
+
+    pub fn build(self) -> Result<Condition, ConditionError> {
+        let Self { instance, items } = self;
+        let topic_expression = &instance.topic_expression;
+        for part in topic_expression.split('/') {
+            if let Some((prefix, _)) = part.split_once(':') {
+                if !KNOWN_PREFIXES.contains(&prefix) {
+                    return Err(ConditionError::UnknownPrefix {
+                        topic_expression: topic_expression.clone(),
+                        prefix: prefix.to_string(),
+                    });
+                }
+            }
+        }
+
+        if items.is_empty() {
+            return Err(ConditionError::NoFilter {
+                topic_expression: topic_expression.clone(),
+            });
+        }
+
+        let mut filters = Vec::new();
+        for (name, value) in items {
+            let item =
+                instance
+                    .simple_item(&name)
+                    .ok_or_else(|| ConditionError::UnknownSimpleItem {
+                        topic_expression: topic_expression.clone(),
+                        name: name.clone(),
+                    })?;
+            validate_value(item, &value)?;
+            filters.push(format!(
+                "boolean(//SimpleItem[@Name={} and @Value={}])",
+                quote(&name)?,
+                quote(&value)?
+            ));
+        }
+
+        Ok(Condition {
+            topic_expression: topic_expression.clone(),
+            message_content: filters.join(" and "),
+        })
+    }
+}
+
+fn validate_value(item: &SimpleItemInstance, value: &str) -> Result<(), ConditionError> {
+    let allowed = if !item.values.is_empty() {
+        item.values.iter().any(|v| v.value == value)
+    } else {
+        match item.value_type.split(':').next_back() {
+            Some("boolean") => matches!(value, "0" | "1" | "true" | "false"),
+            Some("int" | "integer" | "long" | "short" | "byte") => value.parse::<i64>().is_ok(),
+            Some("unsignedInt" | "unsignedLong" | "unsignedShort" | "unsignedByte") => {
+                value.parse::<u64>().is_ok()
+            }
+            _ => true,
+        }
+    };
+    if allowed {
+        Ok(())
+    } else {
+        Err(ConditionError::InvalidValue {
+            name: item.name.clone(),
+            value_type: item.value_type.clone(),
+            value: value.to_string(),
+        })
+    }
+}
+
+/// Quote a string as an XPath 1.0 literal.
+fn quote(s: &str) -> Result<String, ConditionError> {
+    if !s.contains('"') {
+        Ok(format!("\"{s}\""))
+    } else if !s.contains('\'') {
+        Ok(format!("'{s}'"))
+    } else {
+        Err(ConditionError::UnquotableValue(s.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{apis::event1::EventInstances, protocol_helpers::soap_http::SoapResponse};
+
+    fn event_instances() -> EventInstances {
+        EventInstances::from_envelope(include_str!(
+            "../event1/examples/get_event_instances_200_response.xml"
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn can_build_condition_from_event_instance() {
+        let instances = event_instances();
+        let instance = instances
+            .find("tns1:Device/tnsaxis:IO/VirtualInput")
+            .unwrap();
+        let condition = Condition::builder(instance)
+            .simple_item("port", "2")
+            .state(true)
+            .unwrap()
+            .build()
+            .unwrap();
+        assert_eq!(
+            condition.topic_expression,
+            "tns1:Device/tnsaxis:IO/VirtualInput"
+        );
+        assert_eq!(
+            condition.message_content,
+            r#"boolean(//SimpleItem[@Name="port" and @Value="2"]) and boolean(//SimpleItem[@Name="active" and @Value="1"])"#
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_conditions() {
+        let instances = event_instances();
+        let virtual_input = instances
+            .find("tns1:Device/tnsaxis:IO/VirtualInput")
+            .unwrap();
+        assert!(matches!(
+            Condition::builder(virtual_input)
+                .simple_item("ready", "1")
+                .build(),
+            Err(ConditionError::UnknownSimpleItem { .. })
+        ));
+        assert!(matches!(
+            Condition::builder(virtual_input)
+                .simple_item("port", "4")
+                .build(),
+            Err(ConditionError::InvalidValue { .. })
+        ));
+        assert!(matches!(
+            Condition::builder(virtual_input)
+                .simple_item("active", "yes")
+                .build(),
+            Err(ConditionError::InvalidValue { .. })
+        ));
+
+        assert!(matches!(
+            Condition::builder(virtual_input).build(),
+            Err(ConditionError::NoFilter { .. })
+        ));
+
+        let pulse = instances
+            .find("tnsaxis:UserAlarm/tnsaxis:Recurring/tnsaxis:Pulse")
+            .unwrap();
+        assert!(matches!(
+            Condition::builder(pulse).state(true),
+            Err(ConditionError::NoPropertyState { .. })
+        ));
+    }
+}
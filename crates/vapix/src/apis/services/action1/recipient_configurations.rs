@@ -0,0 +1,220 @@
+use std::convert::Infallible;
+
+use anyhow::Context;
+use quick_xml::escape::partial_escape;
+use serde::{de::IgnoredAny, Deserialize};
+
+use crate::{
+    apis::action1::{Parameter, Parameters, RecipientTemplate},
+    http::{HttpClient, Request},
+    protocol_helpers::{http::Error, soap, soap_http, soap_http::SoapResponse},
+};
+
+const PATH: &str = "vapix/services";
+
+const NAMESPACE: &str = "http://www.axis.com/vapix/ws/action1";
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct AddRecipientConfigurationResponse {
+    #[serde(rename = "ConfigurationID")]
+    pub configuration_id: u16,
+}
+
+pub struct AddRecipientConfigurationRequest {
+    name: Option<String>,
+    template_token: String,
+    parameters: Parameters,
+    expected: Option<Vec<String>>,
+}
+
+impl AddRecipientConfigurationRequest {
+    pub fn new(template_token: &str) -> Self {
+        Self {
+            name: None,
+            template_token: template_token.to_string(),
+            parameters: Parameters::default(),
+            expected: None,
+        }
+    }
+
+    /// Like [`Self::new`] but the parameters are checked against the template before sending.
+    pub fn from_template(template: &RecipientTemplate) -> Self {
+        Self {
+            expected: Some(template.parameters.names()),
+            ..Self::new(&template.template_token)
+        }
+    }
+
+    pub fn name(mut self, name: &str) -> Self {
+        self.name = Some(name.to_string());
+        self
+    }
+
+    pub fn param(mut self, name: &str, value: &str) -> Self {
+        self.parameters.parameter.push(Parameter {
+            name: name.to_string(),
+            value: value.to_string(),
+        });
+        self
+    }
+
+    fn build_params(self) -> anyhow::Result<String> {
+        let Self {
+            name,
+            template_token,
+            parameters,
+            expected,
+        } = self;
+        if let Some(expected) = expected {
+            parameters.check(&template_token, &expected)?;
+        }
+        let mut params = String::new();
+        params.push_str(r#"<NewRecipientConfiguration>"#);
+        if let Some(name) = name {
+            params.push_str(r#"<Name>"#);
+            params.push_str(&partial_escape(&name));
+            params.push_str(r#"</Name>"#);
+        }
+        params.push_str(r#"<TemplateToken>"#);
+        params.push_str(&partial_escape(&template_token));
+        params.push_str(r#"</TemplateToken>"#);
+        params.push_str(&quick_xml::se::to_string(&parameters)?);
+        params.push_str(r#"</NewRecipientConfiguration>"#);
+        Ok(params)
+    }
+
+    pub fn try_into_envelope(self) -> anyhow::Result<String> {
+        Ok(soap::envelope(
+            NAMESPACE,
+            "AddRecipientConfiguration",
+            Some(&self.build_params()?),
+        ))
+    }
+
+    pub async fn send(
+        self,
+        client: &(impl HttpClient + Sync),
+    ) -> Result<AddRecipientConfigurationResponse, Error<Infallible>> {
+        let envelope = self.try_into_envelope().map_err(Error::Request)?;
+        let request = Request::new(reqwest::Method::POST, PATH.to_string()).soap(envelope);
+        soap_http::send_request(client, request).await
+    }
+}
+
+pub struct RemoveRecipientConfigurationRequest {
+    configuration_id: u16,
+}
+
+impl RemoveRecipientConfigurationRequest {
+    pub fn new(configuration_id: u16) -> Self {
+        Self { configuration_id }
+    }
+
+    pub fn into_envelope(self) -> String {
+        let mut params = String::new();
+        params.push_str(r#"<ConfigurationID>"#);
+        params.push_str(&self.configuration_id.to_string());
+        params.push_str(r#"</ConfigurationID>"#);
+        soap::envelope(NAMESPACE, "RemoveRecipientConfiguration", Some(&params))
+    }
+
+    pub async fn send(self, client: &(impl HttpClient + Sync)) -> Result<(), Error<Infallible>> {
+        let request =
+            Request::new(reqwest::Method::POST, PATH.to_string()).soap(self.into_envelope());
+        let RemoveRecipientConfigurationResponse = soap_http::send_request(client, request).await?;
+        Ok(())
+    }
+}
+
+struct RemoveRecipientConfigurationResponse;
+
+impl SoapResponse for RemoveRecipientConfigurationResponse {
+    fn from_envelope(s: &str) -> anyhow::Result<Self> {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "PascalCase")]
+        struct Envelope {
+            #[expect(dead_code, reason = "Required for shape validation")]
+            body: Body,
+        }
+        #[derive(Deserialize)]
+        struct Body {
+            #[serde(rename = "RemoveRecipientConfigurationResponse")]
+            _inner: IgnoredAny,
+        }
+        let Envelope { .. } = quick_xml::de::from_str(s)
+            .with_context(|| format!("Could not parse text; text: {s}"))?;
+        Ok(Self)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct RecipientConfiguration {
+    #[serde(rename = "ConfigurationID")]
+    pub configuration_id: u16,
+    #[serde(default)]
+    pub name: String,
+    pub template_token: String,
+    #[serde(default)]
+    pub parameters: Parameters,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct RecipientConfigurations {
+    #[serde(default)]
+    pub recipient_configuration: Vec<RecipientConfiguration>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct GetRecipientConfigurationsResponse {
+    pub recipient_configurations: RecipientConfigurations,
+}
+
+#[derive(Debug, Default)]
+pub struct GetRecipientConfigurationsRequest;
+
+impl GetRecipientConfigurationsRequest {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn into_envelope(self) -> String {
+        soap::envelope(NAMESPACE, "GetRecipientConfigurations", None)
+    }
+
+    pub async fn send(
+        self,
+        client: &(impl HttpClient + Sync),
+    ) -> Result<GetRecipientConfigurationsResponse, Error<Infallible>> {
+        let request =
+            Request::new(reqwest::Method::POST, PATH.to_string()).soap(self.into_envelope());
+        soap_http::send_request(client, request).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol_helpers::soap::parse_soap;
+
+    #[test]
+    fn can_deserialize_add_recipient_configuration_200_response() {
+        let text = include_str!("examples/add_recipient_configuration_200_response.xml");
+        let data = parse_soap::<AddRecipientConfigurationResponse>(text).unwrap();
+        assert_eq!(data.configuration_id, 3);
+    }
+
+    #[test]
+    fn can_deserialize_get_recipient_configurations_200_response() {
+        let text = include_str!("examples/get_recipient_configurations_200_response.xml");
+        let data = parse_soap::<GetRecipientConfigurationsResponse>(text).unwrap();
+        let configuration = &data.recipient_configurations.recipient_configuration[0];
+        assert_eq!(configuration.configuration_id, 3);
+        assert_eq!(configuration.name, "Webhook");
+        assert_eq!(configuration.template_token, "com.axis.recipient.https");
+        assert_eq!(configuration.parameters.parameter.len(), 5);
+    }
+}
@@ -0,0 +1,195 @@
+use std::convert::Infallible;
+
+use serde::Deserialize;
+
+use crate::{
+    apis::action1::Parameters,
+    http::{HttpClient, Request},
+    protocol_helpers::{http::Error, soap, soap_http},
+};
+
+const PATH: &str = "vapix/services";
+
+const NAMESPACE: &str = "http://www.axis.com/vapix/ws/action1";
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ActionTemplate {
+    pub template_token: String,
+    /// The token of the recipient template that configurations of this template refer to.
+    pub recipient_template: Option<String>,
+    #[serde(default)]
+    pub parameters: Parameters,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ActionTemplates {
+    #[serde(default)]
+    pub action_template: Vec<ActionTemplate>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct GetActionTemplatesResponse {
+    pub action_templates: ActionTemplates,
+}
+
+impl GetActionTemplatesResponse {
+    pub fn find(&self, template_token: &str) -> Option<&ActionTemplate> {
+        self.action_templates
+            .action_template
+            .iter()
+            .find(|t| t.template_token == template_token)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct GetActionTemplatesRequest;
+
+impl GetActionTemplatesRequest {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn into_envelope(self) -> String {
+        soap::envelope(NAMESPACE, "GetActionTemplates", None)
+    }
+
+    pub async fn send(
+        self,
+        client: &(impl HttpClient + Sync),
+    ) -> Result<GetActionTemplatesResponse, Error<Infallible>> {
+        let request =
+            Request::new(reqwest::Method::POST, PATH.to_string()).soap(self.into_envelope());
+        soap_http::send_request(client, request).await
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct RecipientTemplate {
+    pub template_token: String,
+    #[serde(default)]
+    pub parameters: Parameters,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct RecipientTemplates {
+    #[serde(default)]
+    pub recipient_template: Vec<RecipientTemplate>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct GetRecipientTemplatesResponse {
+    pub recipient_templates: RecipientTemplates,
+}
+
+impl GetRecipientTemplatesResponse {
+    pub fn find(&self, template_token: &str) -> Option<&RecipientTemplate> {
+        self.recipient_templates
+            .recipient_template
+            .iter()
+            .find(|t| t.template_token == template_token)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct GetRecipientTemplatesRequest;
+
+impl GetRecipientTemplatesRequest {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn into_envelope(self) -> String {
+        soap::envelope(NAMESPACE, "GetRecipientTemplates", None)
+    }
+
+    pub async fn send(
+        self,
+        client: &(impl HttpClient + Sync),
+    ) -> Result<GetRecipientTemplatesResponse, Error<Infallible>> {
+        let request =
+            Request::new(reqwest::Method::POST, PATH.to_string()).soap(self.into_envelope());
+        soap_http::send_request(client, request).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{apis::action1::AddActionConfigurationRequest, protocol_helpers::soap::parse_soap};
+
+    #[test]
+    fn can_deserialize_get_action_templates_200_response() {
+        let text = include_str!("examples/get_action_templates_200_response.xml");
+        let data = parse_soap::<GetActionTemplatesResponse>(text).unwrap();
+        assert_eq!(data.action_templates.action_template.len(), 2);
+        let led = data.find("com.axis.action.fixed.ledcontrol").unwrap();
+        assert_eq!(led.recipient_template, None);
+        let names: Vec<_> = led
+            .parameters
+            .parameter
+            .iter()
+            .map(|p| p.name.as_str())
+            .collect();
+        assert_eq!(names, ["led", "color", "duration", "interval"]);
+        let https = data
+            .find("com.axis.action.fixed.notification.https")
+            .unwrap();
+        assert_eq!(
+            https.recipient_template.as_deref(),
+            Some("com.axis.recipient.https")
+        );
+    }
+
+    #[test]
+    fn can_deserialize_get_recipient_templates_200_response() {
+        let text = include_str!("examples/get_recipient_templates_200_response.xml");
+        let data = parse_soap::<GetRecipientTemplatesResponse>(text).unwrap();
+        let https = data.find("com.axis.recipient.https").unwrap();
+        assert_eq!(https.parameters.parameter.len(), 5);
+    }
+
+    #[test]
+    fn configuration_is_checked_against_template() {
+        let text = include_str!("examples/get_action_templates_200_response.xml");
+        let data = parse_soap::<GetActionTemplatesResponse>(text).unwrap();
+        let led = data.find("com.axis.action.fixed.ledcontrol").unwrap();
+
+        let error = AddActionConfigurationRequest::from_template(led)
+            .param("color", "green,none")
+            .param("duration", "1")
+            .param("interval", "250")
+            .try_into_envelope()
+            .unwrap_err();
+        assert!(
+            format!("{error}").contains("requires parameter led"),
+            "{error}"
+        );
+
+        let error = AddActionConfigurationRequest::from_template(led)
+            .param("led", "statusled")
+            .param("color", "green,none")
+            .param("duration", "1")
+            .param("interval", "250")
+            .param("colour", "green")
+            .try_into_envelope()
+            .unwrap_err();
+        assert!(
+            format!("{error}").contains("has no parameter colour"),
+            "{error}"
+        );
+
+        AddActionConfigurationRequest::from_template(led)
+            .param("led", "statusled")
+            .param("color", "green,none")
+            .param("duration", "1")
+            .param("interval", "250")
+            .try_into_envelope()
+            .unwrap();
+    }
+}
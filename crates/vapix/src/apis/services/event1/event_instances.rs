@@ -0,0 +1,361 @@
+use std::convert::Infallible;
+
+use anyhow::Context;
+use quick_xml::{
+    escape::unescape,
+    events::{BytesStart, Event},
+    Reader,
+};
+
+use crate::{
+    http::{HttpClient, Request},
+    protocol_helpers::{http::Error, soap, soap_http, soap_http::SoapResponse},
+};
+
+const PATH: &str = "vapix/services";
+
+/// A value that a simple item is declared to take.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AllowedValue {
+    pub value: String,
+    pub nice_name: Option<String>,
+}
+
+/// The declaration of a simple item in the source, key or data of a message.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SimpleItemInstance {
+    pub name: String,
+    /// The XML schema type of the value, e.g. `xsd:boolean`.
+    pub value_type: String,
+    pub nice_name: Option<String>,
+    /// Whether this item carries the state of a property event.
+    pub is_property_state: bool,
+    /// The values that the item may take, if the device enumerates them.
+    pub values: Vec<AllowedValue>,
+}
+
+#[derive(Clone, Debug)]
+pub struct MessageInstance {
+    /// The topic path with namespace prefixes stripped.
+    pub topic: Vec<String>,
+    /// The topic path with namespace prefixes preserved.
+    ///
+    /// Example: `"tns1:Device/tnsaxis:IO/VirtualInput"`.
+    pub topic_expression: String,
+    /// The nice name of the innermost topic, if any.
+    pub nice_name: Option<String>,
+    /// Whether the event is a property event, i.e. it describes a state rather than an instant.
+    pub is_property: bool,
+    pub source: Vec<SimpleItemInstance>,
+    pub key: Vec<SimpleItemInstance>,
+    pub data: Vec<SimpleItemInstance>,
+}
+
+impl MessageInstance {
+    fn items_mut(&mut self, section: Section) -> &mut Vec<SimpleItemInstance> {
+        match section {
+            Section::Source => &mut self.source,
+            Section::Key => &mut self.key,
+            Section::Data => &mut self.data,
+        }
+    }
+
+    /// Find the declaration of the simple item called `name` in the source, key or data.
+    pub fn simple_item(&self, name: &str) -> Option<&SimpleItemInstance> {
+        self.source
+            .iter()
+            .chain(self.key.iter())
+            .chain(self.data.iter())
+            .find(|i| i.name == name)
+    }
+
+    /// The data item that carries the state of this property event, if any.
+    pub fn property_state(&self) -> Option<&SimpleItemInstance> {
+        self.data.iter().find(|i| i.is_property_state)
+    }
+}
+
+#[derive(Debug)]
+pub struct EventInstances {
+    pub message_instances: Vec<MessageInstance>,
+}
+
+impl EventInstances {
+    /// Find the message instance with the given topic expression.
+    pub fn find(&self, topic_expression: &str) -> Option<&MessageInstance> {
+        self.message_instances
+            .iter()
+            .find(|m| m.topic_expression == topic_expression)
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Section {
+    Source,
+    Key,
+    Data,
+}
+
+impl Section {
+    fn from_local_name(name: &[u8]) -> Option<Self> {
+        match name {
+            b"SourceInstance" => Some(Self::Source),
+            b"KeyInstance" => Some(Self::Key),
+            b"DataInstance" => Some(Self::Data),
+            _ => None,
+        }
+    }
+}
+
+fn attribute(e: &BytesStart, local_name: &[u8]) -> anyhow::Result<Option<String>> {
+    for attr in e.attributes() {
+        let attr = attr?;
+        if attr.key.local_name().as_ref() == local_name {
+            return Ok(Some(attr.unescape_value()?.into_owned()));
+        }
+    }
+    Ok(None)
+}
+
+fn flag(e: &BytesStart, local_name: &[u8]) -> anyhow::Result<bool> {
+    Ok(attribute(e, local_name)?.is_some_and(|v| v == "true" || v == "1"))
+}
+
+fn simple_item_instance(e: &BytesStart) -> anyhow::Result<SimpleItemInstance> {
+    Ok(SimpleItemInstance {
+        name: attribute(e, b"Name")?.context("SimpleItemInstance has no Name")?,
+        value_type: attribute(e, b"Type")?.unwrap_or_default(),
+        nice_name: attribute(e, b"NiceName")?,
+        is_property_state: flag(e, b"isPropertyState")?,
+        values: Vec::new(),
+    })
+}
+
+/// Parser state for the message instance currently being read.
+struct Partial {
+    message: MessageInstance,
+    section: Option<Section>,
+    /// The value currently being read, if any.
+    value: Option<AllowedValue>,
+}
+
+impl Partial {
+    fn current_item(&mut self) -> Option<&mut SimpleItemInstance> {
+        let section = self.section?;
+        self.message.items_mut(section).last_mut()
+    }
+
+    fn start(&mut self, e: &BytesStart, empty: bool) -> anyhow::Result<()> {
+        let local_name = e.local_name();
+        if let Some(section) = Section::from_local_name(local_name.as_ref()) {
+            self.section = (!empty).then_some(section);
+        } else if local_name.as_ref() == b"SimpleItemInstance" {
+            let section = self
+                .section
+                .context("SimpleItemInstance outside of a source, key or data instance")?;
+            self.message
+                .items_mut(section)
+                .push(simple_item_instance(e)?);
+        } else if local_name.as_ref() == b"Value" {
+            let value = AllowedValue {
+                value: String::new(),
+                nice_name: attribute(e, b"NiceName")?,
+            };
+            if empty {
+                if let Some(item) = self.current_item() {
+                    item.values.push(value);
+                }
+            } else {
+                self.value = Some(value);
+            }
+        }
+        Ok(())
+    }
+
+    fn end(&mut self, local_name: &[u8]) {
+        if Section::from_local_name(local_name).is_some() {
+            self.section = None;
+        } else if local_name == b"Value" {
+            if let Some(value) = self.value.take() {
+                if let Some(item) = self.current_item() {
+                    item.values.push(value);
+                }
+            }
+        }
+    }
+
+    fn text(&mut self, text: &str) {
+        if let Some(value) = self.value.as_mut() {
+            value.value.push_str(text);
+        }
+    }
+}
+
+impl SoapResponse for EventInstances {
+    fn from_envelope(s: &str) -> anyhow::Result<Self> {
+        let mut message_instances = Vec::new();
+        let mut reader = Reader::from_str(s);
+        // Qualified name and nice name of every open element.
+        let mut stack: Vec<(String, Option<String>)> = Vec::new();
+        let mut partial: Option<Partial> = None;
+        let mut buf = Vec::new();
+        loop {
+            match reader.read_event_into(&mut buf)? {
+                Event::Start(e) => {
+                    let name = String::from_utf8_lossy(e.name().as_ref()).to_string();
+                    if let Some(partial) = partial.as_mut() {
+                        partial.start(&e, false)?;
+                    } else if e.local_name().as_ref() == b"MessageInstance" {
+                        partial = Some(Partial {
+                            message: message_instance(&stack, flag(&e, b"isProperty")?),
+                            section: None,
+                            value: None,
+                        });
+                    }
+                    stack.push((name, attribute(&e, b"NiceName")?));
+                }
+                Event::Empty(e) => {
+                    if let Some(partial) = partial.as_mut() {
+                        partial.start(&e, true)?;
+                    } else if e.local_name().as_ref() == b"MessageInstance" {
+                        message_instances.push(message_instance(&stack, flag(&e, b"isProperty")?));
+                    }
+                }
+                Event::End(e) => {
+                    stack.pop();
+                    if e.local_name().as_ref() == b"MessageInstance" {
+                        if let Some(Partial { message, .. }) = partial.take() {
+                            message_instances.push(message);
+                        }
+                    } else if let Some(partial) = partial.as_mut() {
+                        partial.end(e.local_name().as_ref());
+                    }
+                }
+                Event::Text(e) => {
+                    if let Some(partial) = partial.as_mut() {
+                        partial.text(&e.decode()?);
+                    }
+                }
+                Event::CData(e) => {
+                    if let Some(partial) = partial.as_mut() {
+                        partial.text(&e.decode()?);
+                    }
+                }
+                Event::GeneralRef(e) => {
+                    if let Some(partial) = partial.as_mut() {
+                        partial.text(&unescape(&format!("&{};", e.decode()?))?);
+                    }
+                }
+                Event::Eof => break,
+                _ => {}
+            }
+            buf.clear();
+        }
+        Ok(Self { message_instances })
+    }
+}
+
+fn message_instance(stack: &[(String, Option<String>)], is_property: bool) -> MessageInstance {
+    let topic: Vec<&(String, Option<String>)> = stack
+        .iter()
+        .skip_while(|(n, _)| n.as_str() != "wstop:TopicSet") // skip until TopicSet
+        .skip(1)
+        .collect();
+    MessageInstance {
+        topic: topic
+            .iter()
+            .map(|(n, _)| n.split(':').next_back().unwrap().to_string()) // strip namespace prefix
+            .collect(),
+        topic_expression: topic
+            .iter()
+            .map(|(n, _)| n.as_str())
+            .collect::<Vec<_>>()
+            .join("/"),
+        nice_name: topic.last().and_then(|(_, n)| n.clone()),
+        is_property,
+        source: Vec::new(),
+        key: Vec::new(),
+        data: Vec::new(),
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct GetEventInstancesRequest;
+
+impl GetEventInstancesRequest {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn into_envelope(self) -> String {
+        soap::envelope(
+            "http://www.axis.com/vapix/ws/event1",
+            "GetEventInstances",
+            None,
+        )
+    }
+
+    pub async fn send(
+        self,
+        client: &(impl HttpClient + Sync),
+    ) -> Result<EventInstances, Error<Infallible>> {
+        let request =
+            Request::new(reqwest::Method::POST, PATH.to_string()).soap(self.into_envelope());
+        soap_http::send_request(client, request).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_parse_get_event_instances_response() {
+        let text = include_str!("examples/get_event_instances_200_response.xml");
+        let instances = EventInstances::from_envelope(text).unwrap();
+        assert_eq!(instances.message_instances.len(), 3);
+
+        let virtual_input = instances
+            .find("tns1:Device/tnsaxis:IO/VirtualInput")
+            .unwrap();
+        assert_eq!(virtual_input.topic, ["Device", "IO", "VirtualInput"]);
+        assert_eq!(virtual_input.nice_name.as_deref(), Some("Virtual input"));
+        assert!(virtual_input.is_property);
+        assert!(virtual_input.key.is_empty());
+        let port = virtual_input.simple_item("port").unwrap();
+        assert_eq!(port.value_type, "xsd:int");
+        assert_eq!(port.nice_name.as_deref(), Some("Port"));
+        assert!(!port.is_property_state);
+        let ports: Vec<_> = port.values.iter().map(|v| v.value.as_str()).collect();
+        assert_eq!(ports, ["1", "2", "3"]);
+        let active = virtual_input.property_state().unwrap();
+        assert_eq!(active.name, "active");
+        assert_eq!(active.value_type, "xsd:boolean");
+        assert!(active.values.is_empty());
+
+        let ready = instances
+            .find("tns1:Device/tnsaxis:Status/SystemReady")
+            .unwrap();
+        assert_eq!(ready.property_state().unwrap().nice_name, None);
+    }
+
+    #[test]
+    fn can_parse_nested_topics_and_value_nice_names() {
+        let text = include_str!("examples/get_event_instances_200_response.xml");
+        let instances = EventInstances::from_envelope(text).unwrap();
+        let pulse = instances
+            .find("tnsaxis:UserAlarm/tnsaxis:Recurring/tnsaxis:Pulse")
+            .unwrap();
+        assert!(!pulse.is_property);
+        assert_eq!(pulse.nice_name.as_deref(), Some("Schedule"));
+        assert!(pulse.property_state().is_none());
+        let id = pulse.simple_item("id").unwrap();
+        assert_eq!(
+            id.values[1],
+            AllowedValue {
+                value: "com.axis.schedules.office_hours".to_string(),
+                nice_name: Some("Office hours".to_string()),
+            }
+        );
+    }
+}
@@ -0,0 +1,618 @@
+//! Bindings for ONVIF pull-point subscriptions.
+//!
+//! These make it possible to observe events using only SOAP over HTTP, which is useful when
+//! WebSockets are not available, e.g. when the device is behind a proxy.
+//!
+//! A subscription is created with [`CreatePullPointSubscriptionRequest`] and the returned
+//! [`SubscriptionReference`] is then used to [pull](PullMessagesRequest),
+//! [renew](RenewRequest) and [unsubscribe](UnsubscribeRequest).
+
+use std::{convert::Infallible, time::Duration};
+
+use anyhow::Context;
+use quick_xml::escape::escape;
+use serde::{de::IgnoredAny, Deserialize};
+use url::{Position, Url};
+
+use crate::{
+    http::{HttpClient, Request},
+    protocol_helpers::{http::Error, soap, soap::parse_soap, soap_http, soap_http::SoapResponse},
+};
+
+const PATH: &str = "vapix/services";
+
+const EVENTS_NAMESPACE: &str = "http://www.onvif.org/ver10/events/wsdl";
+
+const NOTIFICATION_NAMESPACE: &str = "http://docs.oasis-open.org/wsn/b-2";
+
+const ADDRESSING_NAMESPACE: &str = "http://www.w3.org/2005/08/addressing";
+
+const AXIS_EVENT_NAMESPACE: &str = "http://www.axis.com/2009/event";
+
+fn xs_duration(duration: Duration) -> String {
+    format!("PT{}S", duration.as_secs())
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct RawReferenceParameters {
+    subscription_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct RawSubscriptionReference {
+    address: String,
+    reference_parameters: Option<RawReferenceParameters>,
+}
+
+/// Identifies a subscription in subsequent requests.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(from = "RawSubscriptionReference")]
+pub struct SubscriptionReference {
+    /// The endpoint of the subscription manager.
+    ///
+    /// Only the path of this URL is used; requests are sent to the host of the client.
+    pub address: String,
+    /// Reference parameter that the device uses to tell subscriptions apart.
+    pub subscription_id: Option<String>,
+}
+
+impl From<RawSubscriptionReference> for SubscriptionReference {
+    fn from(raw: RawSubscriptionReference) -> Self {
+        let RawSubscriptionReference {
+            address,
+            reference_parameters,
+        } = raw;
+        Self {
+            address,
+            subscription_id: reference_parameters.and_then(|p| p.subscription_id),
+        }
+    }
+}
+
+impl SubscriptionReference {
+    fn path(&self) -> anyhow::Result<String> {
+        let url = Url::parse(&self.address)
+            .with_context(|| format!("Could not parse address {}", self.address))?;
+        Ok(url[Position::BeforePath..]
+            .trim_start_matches('/')
+            .to_string())
+    }
+
+    fn header(&self, action: &str) -> String {
+        let mut s = String::new();
+        s.push_str(r#"<wsa:Action xmlns:wsa=""#);
+        s.push_str(ADDRESSING_NAMESPACE);
+        s.push_str(r#"">"#);
+        s.push_str(action);
+        s.push_str(r#"</wsa:Action>"#);
+        s.push_str(r#"<wsa:To xmlns:wsa=""#);
+        s.push_str(ADDRESSING_NAMESPACE);
+        s.push_str(r#"">"#);
+        s.push_str(&escape(self.address.as_str()));
+        s.push_str(r#"</wsa:To>"#);
+        if let Some(subscription_id) = &self.subscription_id {
+            s.push_str(r#"<dom0:SubscriptionId xmlns:dom0=""#);
+            s.push_str(AXIS_EVENT_NAMESPACE);
+            s.push_str(r#"" xmlns:wsa=""#);
+            s.push_str(ADDRESSING_NAMESPACE);
+            s.push_str(r#"" wsa:IsReferenceParameter="true">"#);
+            s.push_str(&escape(subscription_id.as_str()));
+            s.push_str(r#"</dom0:SubscriptionId>"#);
+        }
+        s
+    }
+
+    fn envelope(
+        &self,
+        action: &str,
+        namespace: &str,
+        method: &str,
+        params: Option<&str>,
+    ) -> anyhow::Result<Request> {
+        let envelope =
+            soap::envelope_with_header(Some(&self.header(action)), namespace, method, params);
+        Ok(Request::new(reqwest::Method::POST, self.path()?).soap(envelope))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct CreatePullPointSubscriptionResponse {
+    pub subscription_reference: SubscriptionReference,
+    pub current_time: String,
+    pub termination_time: String,
+}
+
+/// Create a subscription from which notifications can be pulled.
+#[derive(Clone, Debug, Default)]
+pub struct CreatePullPointSubscriptionRequest {
+    topic_filters: Vec<String>,
+    initial_termination_time: Option<Duration>,
+}
+
+impl CreatePullPointSubscriptionRequest {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Include events with a topic matching `topic_filter`.
+    ///
+    /// Example: `"tns1:Device/tnsaxis:IO/VirtualInput"`.
+    ///
+    /// If no filter is added, then the subscription includes all events.
+    pub fn topic_filter(mut self, topic_filter: impl Into<String>) -> Self {
+        self.topic_filters.push(topic_filter.into());
+        self
+    }
+
+    /// The time after which the subscription expires unless it is renewed.
+    pub fn initial_termination_time(mut self, duration: Duration) -> Self {
+        self.initial_termination_time = Some(duration);
+        self
+    }
+
+    pub fn into_envelope(self) -> String {
+        let Self {
+            topic_filters,
+            initial_termination_time,
+        } = self;
+        let mut params = String::new();
+        if !topic_filters.is_empty() {
+            params.push_str(r#"<Filter>"#);
+            params.push_str(r#"<TopicExpression Dialect="http://www.onvif.org/ver10/tev/topicExpression/ConcreteSet" xmlns=""#);
+            params.push_str(NOTIFICATION_NAMESPACE);
+            params.push_str(r#"" xmlns:tns1="http://www.onvif.org/ver10/topics" xmlns:tnsaxis="http://www.axis.com/2009/event/topics">"#);
+            params.push_str(&escape(topic_filters.join("|").as_str()));
+            params.push_str(r#"</TopicExpression>"#);
+            params.push_str(r#"</Filter>"#);
+        }
+        if let Some(duration) = initial_termination_time {
+            params.push_str(r#"<InitialTerminationTime>"#);
+            params.push_str(&xs_duration(duration));
+            params.push_str(r#"</InitialTerminationTime>"#);
+        }
+        soap::envelope(
+            EVENTS_NAMESPACE,
+            "CreatePullPointSubscription",
+            Some(&params),
+        )
+    }
+
+    pub async fn send(
+        self,
+        client: &(impl HttpClient + Sync),
+    ) -> Result<CreatePullPointSubscriptionResponse, Error<Infallible>> {
+        let request =
+            Request::new(reqwest::Method::POST, PATH.to_string()).soap(self.into_envelope());
+        soap_http::send_request(client, request).await
+    }
+}
+
+/// Whether and how a property event changed.
+///
+/// Stateless events have no property operation.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq)]
+pub enum PropertyOperation {
+    /// The current state, reported when the subscription is created.
+    Initialized,
+    Changed,
+    Deleted,
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
+pub struct SimpleItem {
+    #[serde(rename = "@Name")]
+    pub name: String,
+    #[serde(rename = "@Value")]
+    pub value: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct SimpleItems {
+    #[serde(default, rename = "SimpleItem")]
+    simple_item: Vec<SimpleItem>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct RawMessage {
+    #[serde(rename = "@UtcTime")]
+    utc_time: String,
+    #[serde(default, rename = "@PropertyOperation")]
+    property_operation: Option<PropertyOperation>,
+    #[serde(default)]
+    source: SimpleItems,
+    #[serde(default)]
+    key: SimpleItems,
+    #[serde(default)]
+    data: SimpleItems,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct RawMessageWrapper {
+    message: RawMessage,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawTopic {
+    #[serde(rename = "$text")]
+    value: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct RawNotificationMessage {
+    topic: RawTopic,
+    message: RawMessageWrapper,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct RawPullMessagesResponse {
+    current_time: String,
+    termination_time: String,
+    #[serde(default)]
+    notification_message: Vec<RawNotificationMessage>,
+}
+
+/// An event emitted by the device.
+#[derive(Clone, Debug)]
+pub struct Notification {
+    /// The topic of the event, including namespace prefixes.
+    ///
+    /// Example: `"tns1:Device/tnsaxis:IO/VirtualInput"`.
+    pub topic: String,
+    pub utc_time: String,
+    pub property_operation: Option<PropertyOperation>,
+    pub source: Vec<SimpleItem>,
+    pub key: Vec<SimpleItem>,
+    pub data: Vec<SimpleItem>,
+}
+
+impl Notification {
+    /// The topic without namespace prefixes.
+    ///
+    /// This is the same representation as in [`MessageInstance`](super::MessageInstance).
+    pub fn topic_path(&self) -> Vec<String> {
+        self.topic
+            .split('/')
+            .map(|n| n.split(':').next_back().unwrap_or(n).to_string())
+            .collect()
+    }
+
+    pub fn source_value(&self, name: &str) -> Option<&str> {
+        find_value(&self.source, name)
+    }
+
+    pub fn key_value(&self, name: &str) -> Option<&str> {
+        find_value(&self.key, name)
+    }
+
+    pub fn data_value(&self, name: &str) -> Option<&str> {
+        find_value(&self.data, name)
+    }
+}
+
+fn find_value<'a>(items: &'a [SimpleItem], name: &str) -> Option<&'a str> {
+    items
+        .iter()
+        .find(|i| i.name == name)
+        .map(|i| i.value.as_str())
+}
+
+impl From<RawNotificationMessage> for Notification {
+    fn from(raw: RawNotificationMessage) -> Self {
+        let RawNotificationMessage {
+            topic: RawTopic { value: topic },
+            message:
+                RawMessageWrapper {
+                    message:
+                        RawMessage {
+                            utc_time,
+                            property_operation,
+                            source,
+                            key,
+                            data,
+                        },
+                },
+        } = raw;
+        Self {
+            topic: topic.trim().to_string(),
+            utc_time,
+            property_operation,
+            source: source.simple_item,
+            key: key.simple_item,
+            data: data.simple_item,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct PullMessagesResponse {
+    pub current_time: String,
+    pub termination_time: String,
+    pub notifications: Vec<Notification>,
+}
+
+impl SoapResponse for PullMessagesResponse {
+    fn from_envelope(s: &str) -> anyhow::Result<Self> {
+        let RawPullMessagesResponse {
+            current_time,
+            termination_time,
+            notification_message,
+        } = parse_soap(s)?;
+        Ok(Self {
+            current_time,
+            termination_time,
+            notifications: notification_message
+                .into_iter()
+                .map(Notification::from)
+                .collect(),
+        })
+    }
+}
+
+/// Pull the notifications that have been queued for a subscription.
+///
+/// If the queue is empty, the device waits for up to the timeout before responding.
+#[derive(Clone, Debug)]
+pub struct PullMessagesRequest {
+    subscription: SubscriptionReference,
+    timeout: Duration,
+    message_limit: u32,
+}
+
+impl PullMessagesRequest {
+    pub fn new(subscription: &SubscriptionReference) -> Self {
+        Self {
+            subscription: subscription.clone(),
+            timeout: Duration::from_secs(10),
+            message_limit: 100,
+        }
+    }
+
+    /// The maximum time to wait for notifications when none are queued.
+    ///
+    /// Defaults to 10 seconds.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// The maximum number of notifications to return.
+    ///
+    /// Defaults to 100.
+    pub fn message_limit(mut self, message_limit: u32) -> Self {
+        self.message_limit = message_limit;
+        self
+    }
+
+    pub fn try_into_request(self) -> anyhow::Result<Request> {
+        let mut params = String::new();
+        params.push_str(r#"<Timeout>"#);
+        params.push_str(&xs_duration(self.timeout));
+        params.push_str(r#"</Timeout>"#);
+        params.push_str(r#"<MessageLimit>"#);
+        params.push_str(&self.message_limit.to_string());
+        params.push_str(r#"</MessageLimit>"#);
+        self.subscription.envelope(
+            "http://www.onvif.org/ver10/events/wsdl/PullPointSubscription/PullMessagesRequest",
+            EVENTS_NAMESPACE,
+            "PullMessages",
+            Some(&params),
+        )
+    }
+
+    pub async fn send(
+        self,
+        client: &(impl HttpClient + Sync),
+    ) -> Result<PullMessagesResponse, Error<Infallible>> {
+        let request = self.try_into_request().map_err(Error::Request)?;
+        soap_http::send_request(client, request).await
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct RenewResponse {
+    pub termination_time: String,
+    pub current_time: Option<String>,
+}
+
+/// Extend the lifetime of a subscription.
+#[derive(Clone, Debug)]
+pub struct RenewRequest {
+    subscription: SubscriptionReference,
+    termination_time: Duration,
+}
+
+impl RenewRequest {
+    /// Renew the subscription so that it expires `termination_time` from now.
+    pub fn new(subscription: &SubscriptionReference, termination_time: Duration) -> Self {
+        Self {
+            subscription: subscription.clone(),
+            termination_time,
+        }
+    }
+
+    pub fn try_into_request(self) -> anyhow::Result<Request> {
+        let mut params = String::new();
+        params.push_str(r#"<TerminationTime>"#);
+        params.push_str(&xs_duration(self.termination_time));
+        params.push_str(r#"</TerminationTime>"#);
+        self.subscription.envelope(
+            "http://docs.oasis-open.org/wsn/bw-2/SubscriptionManager/RenewRequest",
+            NOTIFICATION_NAMESPACE,
+            "Renew",
+            Some(&params),
+        )
+    }
+
+    pub async fn send(
+        self,
+        client: &(impl HttpClient + Sync),
+    ) -> Result<RenewResponse, Error<Infallible>> {
+        let request = self.try_into_request().map_err(Error::Request)?;
+        soap_http::send_request(client, request).await
+    }
+}
+
+/// End a subscription before it expires.
+#[derive(Clone, Debug)]
+pub struct UnsubscribeRequest {
+    subscription: SubscriptionReference,
+}
+
+impl UnsubscribeRequest {
+    pub fn new(subscription: &SubscriptionReference) -> Self {
+        Self {
+            subscription: subscription.clone(),
+        }
+    }
+
+    pub fn try_into_request(self) -> anyhow::Result<Request> {
+        self.subscription.envelope(
+            "http://docs.oasis-open.org/wsn/bw-2/SubscriptionManager/UnsubscribeRequest",
+            NOTIFICATION_NAMESPACE,
+            "Unsubscribe",
+            None,
+        )
+    }
+
+    pub async fn send(self, client: &(impl HttpClient + Sync)) -> Result<(), Error<Infallible>> {
+        let request = self.try_into_request().map_err(Error::Request)?;
+        let UnsubscribeResponse = soap_http::send_request(client, request).await?;
+        Ok(())
+    }
+}
+
+struct UnsubscribeResponse;
+
+impl SoapResponse for UnsubscribeResponse {
+    fn from_envelope(s: &str) -> anyhow::Result<Self> {
+        // The body is empty, but the wrapper element must still be present and must be
+        // `UnsubscribeResponse` — anything else (e.g. a `SOAP-ENV:Fault`) should fail.
+        #[derive(Deserialize)]
+        #[serde(rename_all = "PascalCase")]
+        struct Envelope {
+            #[expect(dead_code, reason = "Required for shape validation")]
+            body: Body,
+        }
+        #[derive(Deserialize)]
+        struct Body {
+            #[serde(rename = "UnsubscribeResponse")]
+            _inner: IgnoredAny,
+        }
+        let Envelope { .. } = quick_xml::de::from_str(s)
+            .with_context(|| format!("Could not parse text; text: {s}"))?;
+        Ok(Self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::expect;
+
+    use super::*;
+
+    fn subscription() -> SubscriptionReference {
+        let text = include_str!("examples/create_pull_point_subscription_200_response.xml");
+        parse_soap::<CreatePullPointSubscriptionResponse>(text)
+            .unwrap()
+            .subscription_reference
+    }
+
+    #[test]
+    fn can_deserialize_create_pull_point_subscription_200_response() {
+        let subscription = subscription();
+        assert_eq!(subscription.address, "http://192.0.2.1/onvif/services");
+        assert_eq!(subscription.subscription_id.as_deref(), Some("7"));
+        assert_eq!(subscription.path().unwrap(), "onvif/services");
+    }
+
+    #[test]
+    fn can_deserialize_pull_messages_200_response() {
+        let text = include_str!("examples/pull_messages_200_response.xml");
+        let data = PullMessagesResponse::from_envelope(text).unwrap();
+        expect![[r#"
+            [
+                Notification {
+                    topic: "tns1:Device/tnsaxis:Status/SystemReady",
+                    utc_time: "2024-04-25T13:35:30.123456Z",
+                    property_operation: Some(
+                        Initialized,
+                    ),
+                    source: [],
+                    key: [],
+                    data: [
+                        SimpleItem {
+                            name: "ready",
+                            value: "1",
+                        },
+                    ],
+                },
+                Notification {
+                    topic: "tns1:Device/tnsaxis:IO/VirtualInput",
+                    utc_time: "2024-04-25T13:35:34.654321Z",
+                    property_operation: Some(
+                        Changed,
+                    ),
+                    source: [
+                        SimpleItem {
+                            name: "port",
+                            value: "1",
+                        },
+                    ],
+                    key: [],
+                    data: [
+                        SimpleItem {
+                            name: "active",
+                            value: "1",
+                        },
+                    ],
+                },
+            ]
+        "#]]
+        .assert_debug_eq(&data.notifications);
+        let notification = data.notifications.last().unwrap();
+        assert_eq!(notification.topic_path(), ["Device", "IO", "VirtualInput"]);
+        assert_eq!(notification.source_value("port"), Some("1"));
+        assert_eq!(notification.data_value("active"), Some("1"));
+    }
+
+    #[test]
+    fn can_deserialize_pull_messages_200_empty() {
+        let text = include_str!("examples/pull_messages_200_empty.xml");
+        let data = PullMessagesResponse::from_envelope(text).unwrap();
+        assert!(data.notifications.is_empty());
+    }
+
+    #[test]
+    fn can_deserialize_renew_200_response() {
+        let text = include_str!("examples/renew_200_response.xml");
+        let data = parse_soap::<RenewResponse>(text).unwrap();
+        assert_eq!(data.termination_time, "2024-04-25T13:37:40Z");
+    }
+
+    #[test]
+    fn can_deserialize_unsubscribe_200_response() {
+        let text = include_str!("examples/unsubscribe_200_response.xml");
+        let UnsubscribeResponse = UnsubscribeResponse::from_envelope(text).unwrap();
+    }
+
+    #[test]
+    fn pull_messages_request_is_addressed_to_subscription() {
+        let request = PullMessagesRequest::new(&subscription())
+            .timeout(Duration::from_secs(5))
+            .message_limit(10)
+            .try_into_request()
+            .unwrap();
+        assert_eq!(request.path, "onvif/services");
+        let body = request.body.unwrap();
+        expect![[r#"<soap:Envelope xmlns:soap="http://www.w3.org/2003/05/soap-envelope"><soap:Header><wsa:Action xmlns:wsa="http://www.w3.org/2005/08/addressing">http://www.onvif.org/ver10/events/wsdl/PullPointSubscription/PullMessagesRequest</wsa:Action><wsa:To xmlns:wsa="http://www.w3.org/2005/08/addressing">http://192.0.2.1/onvif/services</wsa:To><dom0:SubscriptionId xmlns:dom0="http://www.axis.com/2009/event" xmlns:wsa="http://www.w3.org/2005/08/addressing" wsa:IsReferenceParameter="true">7</dom0:SubscriptionId></soap:Header><soap:Body><PullMessages xmlns="http://www.onvif.org/ver10/events/wsdl"><Timeout>PT5S</Timeout><MessageLimit>10</MessageLimit></PullMessages></soap:Body></soap:Envelope>"#]]
+        .assert_eq(std::str::from_utf8(body.as_bytes().unwrap()).unwrap());
+    }
+}
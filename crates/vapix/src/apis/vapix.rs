@@ -0,0 +1,2 @@
+//! A collection of APIs that can be found under the `/vapix/` path, other than the web services.
+pub mod event_stream_1;
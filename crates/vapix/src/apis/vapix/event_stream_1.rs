@@ -0,0 +1,319 @@
+//! Event streaming over the WebSocket metadata channel.
+//!
+//! Unlike the [`event1`](crate::apis::event1) service, which can only describe what events a
+//! device may emit, this API pushes notifications to the client as they happen.
+//!
+//! The channel is served on `/vapix/ws-data-stream` and requires a session token that is obtained
+//! from `/axis-cgi/wssession.cgi` using regular HTTP authentication.
+
+use std::{
+    collections::BTreeMap,
+    pin::Pin,
+    task::{ready, Poll},
+};
+
+use anyhow::Context;
+use futures_util::{SinkExt, Stream, StreamExt};
+use log::{debug, trace};
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio_tungstenite::{tungstenite, WebSocketStream};
+
+use crate::{
+    http::{HttpClient, Request},
+    protocol_helpers::{http::Error, json_rpc},
+    Client,
+};
+
+const SESSION_PATH: &str = "axis-cgi/wssession.cgi";
+
+const STREAM_PATH: &str = "vapix/ws-data-stream";
+
+const CONFIGURE: &str = "events:configure";
+
+const NOTIFY: &str = "events:notify";
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct EventFilter {
+    topic_filter: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    content_filter: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ConfigureParams {
+    event_filter_list: Vec<EventFilter>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ConfigureBody<'a> {
+    api_version: &'static str,
+    method: &'static str,
+    params: &'a ConfigureParams,
+}
+
+/// Subscribe to the events matching one or more filters.
+#[derive(Clone, Debug)]
+pub struct SubscribeRequest {
+    params: ConfigureParams,
+}
+
+impl Default for SubscribeRequest {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SubscribeRequest {
+    pub fn new() -> Self {
+        Self {
+            params: ConfigureParams {
+                event_filter_list: Vec::new(),
+            },
+        }
+    }
+
+    /// Include events with a topic matching `topic_filter`.
+    ///
+    /// Example: `"tns1:Device/tnsaxis:IO/VirtualInput"`.
+    pub fn topic_filter(mut self, topic_filter: impl Into<String>) -> Self {
+        self.params.event_filter_list.push(EventFilter {
+            topic_filter: topic_filter.into(),
+            content_filter: None,
+        });
+        self
+    }
+
+    /// Include events with a topic matching `topic_filter` and a message matching
+    /// `content_filter`.
+    ///
+    /// Example: `"boolean(//SimpleItem[@Name=\"active\" and @Value=\"1\"])"`.
+    pub fn filter(
+        mut self,
+        topic_filter: impl Into<String>,
+        content_filter: impl Into<String>,
+    ) -> Self {
+        self.params.event_filter_list.push(EventFilter {
+            topic_filter: topic_filter.into(),
+            content_filter: Some(content_filter.into()),
+        });
+        self
+    }
+
+    pub fn to_message(&self) -> String {
+        // PANICS:
+        // The `unwrap` will never panic because the body consists only of strings.
+        serde_json::to_string(&ConfigureBody {
+            api_version: "1.0",
+            method: CONFIGURE,
+            params: &self.params,
+        })
+        .unwrap()
+    }
+
+    // TODO: Migrate to `HttpClient` when there is a transport abstraction for WebSockets.
+    pub async fn send(self, client: &Client) -> Result<EventStream, Error<json_rpc::Error>> {
+        let token = fetch_session_token(client).await?;
+        let mut inner = client
+            .websocket(&format!("{STREAM_PATH}?sources=events&wssession={token}"))
+            .await
+            .map_err(Error::Transport)?;
+
+        inner
+            .send(tungstenite::Message::text(self.to_message()))
+            .await
+            .context("Failed to send configuration")
+            .map_err(Error::Transport)?;
+
+        loop {
+            let text = match inner.next().await {
+                None => {
+                    return Err(Error::Transport(anyhow::anyhow!(
+                        "Connection closed before the configuration was acknowledged"
+                    )))
+                }
+                Some(Err(e)) => return Err(Error::Transport(e.into())),
+                Some(Ok(tungstenite::Message::Text(text))) => text,
+                Some(Ok(other)) => {
+                    trace!("Ignoring non-text message {other:?}");
+                    continue;
+                }
+            };
+            let envelope = parse_envelope(&text).map_err(Error::Decode)?;
+            if envelope.method != CONFIGURE {
+                debug!("Ignoring {} while waiting for {CONFIGURE}", envelope.method);
+                continue;
+            }
+            return match envelope.error {
+                None => Ok(EventStream { inner }),
+                Some(e) => Err(Error::Service(e)),
+            };
+        }
+    }
+}
+
+async fn fetch_session_token(
+    client: &(impl HttpClient + Sync),
+) -> Result<String, Error<json_rpc::Error>> {
+    let response = client
+        .execute(Request::new(Method::GET, SESSION_PATH.to_string()))
+        .await
+        .map_err(Error::Transport)?;
+    let status = response.status;
+    let text = response
+        .body
+        .with_context(|| format!("Could not fetch text, status was {status}"))
+        .map_err(Error::Transport)?;
+    let token = text.trim();
+    if !status.is_success() || token.is_empty() {
+        return Err(Error::Decode(anyhow::anyhow!(
+            "Unexpected response: {status} {token}"
+        )));
+    }
+    Ok(token.to_string())
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Envelope {
+    method: String,
+    #[serde(default)]
+    params: Option<Value>,
+    #[serde(default)]
+    error: Option<json_rpc::Error>,
+}
+
+fn parse_envelope(text: &str) -> anyhow::Result<Envelope> {
+    serde_json::from_str(text).with_context(|| format!("Could not parse message; text: {text}"))
+}
+
+#[derive(Debug, Deserialize)]
+struct NotifyParams {
+    notification: Notification,
+}
+
+/// Parse a message received on the stream.
+///
+/// Returns `None` for messages that are not event notifications.
+pub fn parse_notification(text: &str) -> anyhow::Result<Option<Notification>> {
+    let Envelope { method, params, .. } = parse_envelope(text)?;
+    if method != NOTIFY {
+        return Ok(None);
+    }
+    let params = params.context("Notification has no params")?;
+    let NotifyParams { notification } = serde_json::from_value(params)
+        .with_context(|| format!("Could not parse notification; text: {text}"))?;
+    Ok(Some(notification))
+}
+
+/// An event emitted by the device.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Notification {
+    /// The topic of the event, including namespace prefixes.
+    ///
+    /// Example: `"tns1:Device/tnsaxis:IO/VirtualInput"`.
+    pub topic: String,
+    /// Milliseconds since the Unix epoch.
+    pub timestamp: u64,
+    pub message: NotificationMessage,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct NotificationMessage {
+    #[serde(default)]
+    pub source: BTreeMap<String, String>,
+    #[serde(default)]
+    pub key: BTreeMap<String, String>,
+    #[serde(default)]
+    pub data: BTreeMap<String, String>,
+}
+
+/// A stream of the notifications matching the filters of a [`SubscribeRequest`].
+///
+/// The stream ends when the device closes the connection.
+pub struct EventStream {
+    inner: WebSocketStream<reqwest::Upgraded>,
+}
+
+impl EventStream {
+    /// Close the connection gracefully.
+    pub async fn close(mut self) -> anyhow::Result<()> {
+        self.inner.close(None).await?;
+        Ok(())
+    }
+}
+
+impl Stream for EventStream {
+    type Item = Result<Notification, Error<json_rpc::Error>>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        loop {
+            let text = match ready!(self.inner.poll_next_unpin(cx)) {
+                None => return Poll::Ready(None),
+                Some(Err(e)) => return Poll::Ready(Some(Err(Error::Transport(e.into())))),
+                Some(Ok(tungstenite::Message::Text(text))) => text,
+                Some(Ok(other)) => {
+                    trace!("Ignoring non-text message {other:?}");
+                    continue;
+                }
+            };
+            match parse_notification(&text) {
+                Ok(Some(notification)) => return Poll::Ready(Some(Ok(notification))),
+                Ok(None) => debug!("Ignoring message that is not a notification: {text}"),
+                Err(e) => return Poll::Ready(Some(Err(Error::Decode(e)))),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::expect;
+
+    use super::*;
+
+    #[test]
+    fn can_serialize_subscribe_request() {
+        let message = SubscribeRequest::new()
+            .topic_filter("tns1:Device/tnsaxis:IO/VirtualInput")
+            .filter(
+                "tns1:Device/tnsaxis:Status/SystemReady",
+                r#"boolean(//SimpleItem[@Name="ready" and @Value="1"])"#,
+            )
+            .to_message();
+        expect![[r#"{"apiVersion":"1.0","method":"events:configure","params":{"eventFilterList":[{"topicFilter":"tns1:Device/tnsaxis:IO/VirtualInput"},{"topicFilter":"tns1:Device/tnsaxis:Status/SystemReady","contentFilter":"boolean(//SimpleItem[@Name=\"ready\" and @Value=\"1\"])"}]}}"#]]
+            .assert_eq(&message);
+    }
+
+    #[test]
+    fn can_parse_notification() {
+        let text = include_str!("event_stream_1/events_notify.json");
+        let notification = parse_notification(text).unwrap().unwrap();
+        assert_eq!(notification.topic, "tns1:Device/tnsaxis:IO/VirtualInput");
+        assert_eq!(notification.message.source["port"], "1");
+        assert!(notification.message.key.is_empty());
+        assert_eq!(notification.message.data["active"], "1");
+    }
+
+    #[test]
+    fn ignores_configure_acknowledgement() {
+        let text = include_str!("event_stream_1/events_configure.json");
+        assert!(parse_notification(text).unwrap().is_none());
+    }
+
+    #[test]
+    fn can_parse_configure_error() {
+        let text = include_str!("event_stream_1/events_configure_error.json");
+        let envelope = parse_envelope(text).unwrap();
+        assert_eq!(envelope.method, CONFIGURE);
+        assert_eq!(envelope.error.unwrap().code, 4002);
+    }
+}
@@ -1,19 +1,47 @@
-use std::sync::{Arc, Mutex};
+use std::{
+    fmt::{Display, Formatter},
+    str::FromStr,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
 
-use anyhow::{bail, Context};
+use anyhow::{bail, ensure, Context};
 use digest_auth::{AuthContext, HttpMethod, WwwAuthenticateHeader};
 use log::{debug, warn};
 use reqwest::{
-    header::{AUTHORIZATION, WWW_AUTHENTICATE},
-    Method, StatusCode,
+    header::{
+        HeaderValue, AUTHORIZATION, CONNECTION, SEC_WEBSOCKET_ACCEPT, SEC_WEBSOCKET_KEY,
+        SEC_WEBSOCKET_VERSION, UPGRADE,
+    },
+    Method, StatusCode, Version,
+};
+use tokio_tungstenite::{
+    tungstenite::{
+        handshake::{client::generate_key, derive_accept_key},
+        protocol::Role,
+    },
+    WebSocketStream,
 };
 use url::{Host, Position, Url};
 
 use crate::{
     apis::system_ready_1::SystemReadyRequest,
-    http::{HttpClient, Request, Response},
+    http::{Body, HttpClient, Request, Response, RetryClient, RetryPolicy},
+    tls,
+    tls::CertificateFingerprint,
 };
 
+mod challenge;
+mod session;
+#[cfg(test)]
+mod test_server;
+
+use challenge::Challenge;
+use session::Session;
+
+/// How long to wait for a device to respond when probing which schemes it supports.
+const SCHEME_PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
 #[derive(Clone)]
 struct Secret(String);
 
@@ -59,27 +87,33 @@ impl DigestAuth {
         header.respond(&ctx).ok().map(|a| a.to_header_string())
     }
 
+    /// Respond to the strongest Digest challenge in a `401 Unauthorized` response.
+    ///
+    /// If the request that got the response was authenticated using `used_nonce`, a new response
+    /// is computed only if that nonce is stale or has been replaced, e.g. because the device
+    /// restarted; otherwise the credentials were rejected and retrying is futile.
     fn respond_to_challenge(
         &self,
         response: &reqwest::Response,
         path: &str,
         method: &str,
+        used_nonce: Option<&str>,
     ) -> Option<(WwwAuthenticateHeader, String)> {
-        let www_auth = response.headers().get(WWW_AUTHENTICATE)?;
-        let www_auth = match www_auth.to_str() {
-            Ok(s) => s,
-            Err(e) => {
-                debug!("WWW-Authenticate header is not valid UTF-8: {e}");
-                return None;
-            }
+        let Some(mut header) = challenge::strongest_digest(response.headers()) else {
+            debug!("Response offers no supported Digest challenge");
+            return None;
         };
-        let mut header = match digest_auth::parse(www_auth) {
-            Ok(h) => h,
-            Err(e) => {
-                debug!("Failed to parse WWW-Authenticate header: {e}");
+        match used_nonce {
+            None => {}
+            Some(_) if header.stale => debug!("Nonce is stale, retrying with a fresh nonce"),
+            Some(nonce) if nonce != header.nonce => {
+                debug!("Nonce has been replaced, retrying with the new nonce")
+            }
+            Some(_) => {
+                debug!("Credentials were rejected, not retrying");
                 return None;
             }
-        };
+        }
         let value = self.compute_header(method, path, &mut header)?;
         Some((header, value))
     }
@@ -89,8 +123,7 @@ impl DigestAuth {
         builder: reqwest::RequestBuilder,
     ) -> Result<reqwest::Response, reqwest::Error> {
         let Some(cloned) = builder.try_clone() else {
-            debug!("Digest auth skipped: request body is not cloneable");
-            return builder.send().await;
+            return self.send_uncloneable(builder).await;
         };
         let Ok(probe) = cloned.build() else {
             debug!("Digest auth skipped: failed to build request for inspection");
@@ -99,45 +132,108 @@ impl DigestAuth {
         let method = probe.method().to_string();
         let path = probe.url()[Position::AfterPort..].to_string();
 
-        let stored_auth_header = self
-            .challenge
-            .lock()
-            .unwrap_or_else(|e| {
-                let mut guard = e.into_inner();
-                *guard = None;
-                guard
-            })
-            .as_mut()
-            .and_then(|h| self.compute_header(&method, &path, h));
+        let stored = self.cached_header(&method, &path);
 
         let Some(pilot) = builder.try_clone() else {
             debug!("Request builder is not cloneable, ignoring any challenges in the response");
-            let builder = match stored_auth_header {
+            let builder = match stored {
                 None => builder,
-                Some(auth_header) => builder.header(AUTHORIZATION, auth_header),
+                Some((_, auth_header)) => builder.header(AUTHORIZATION, auth_header),
             };
             return builder.send().await;
         };
 
-        let response = match stored_auth_header {
+        let used_nonce = stored.as_ref().map(|(nonce, _)| nonce.clone());
+        let response = match stored {
             None => pilot.send().await?,
-            Some(auth_header) => pilot.header(AUTHORIZATION, auth_header).send().await?,
+            Some((_, auth_header)) => pilot.header(AUTHORIZATION, auth_header).send().await?,
         };
 
         if response.status() != StatusCode::UNAUTHORIZED {
             return Ok(response);
         }
 
-        match self.respond_to_challenge(&response, &path, &method) {
-            None => {
-                *self.challenge.lock().unwrap_or_else(|e| e.into_inner()) = None;
-                Ok(response)
-            }
-            Some((header, value)) => {
-                *self.challenge.lock().unwrap_or_else(|e| e.into_inner()) = Some(header);
-                builder.header(AUTHORIZATION, value).send().await
+        let Some((header, value)) =
+            self.respond_to_challenge(&response, &path, &method, used_nonce.as_deref())
+        else {
+            self.set_challenge(None);
+            return Ok(response);
+        };
+        self.set_challenge(Some(header));
+        let response = builder.header(AUTHORIZATION, value).send().await?;
+        if response.status() == StatusCode::UNAUTHORIZED {
+            debug!("Credentials were rejected after retrying with a fresh challenge");
+            self.set_challenge(None);
+        }
+        Ok(response)
+    }
+
+    /// Returns the nonce of the cached challenge and the `Authorization` header responding to it.
+    fn cached_header(&self, method: &str, path: &str) -> Option<(String, String)> {
+        let mut challenge = self.challenge.lock().unwrap_or_else(|e| {
+            let mut guard = e.into_inner();
+            *guard = None;
+            guard
+        });
+        let header = challenge.as_mut()?;
+        let value = self.compute_header(method, path, header)?;
+        Some((header.nonce.clone(), value))
+    }
+
+    fn set_challenge(&self, challenge: Option<WwwAuthenticateHeader>) {
+        *self.challenge.lock().unwrap_or_else(|e| e.into_inner()) = challenge;
+    }
+
+    /// Like [`Self::send`] but for requests whose body can be sent only once, such as streams.
+    ///
+    /// A pilot request without a body is sent first, using the cached challenge if any, so that
+    /// the body is sent only once the credentials and nonce are known to be accepted.
+    async fn send_uncloneable(
+        &self,
+        builder: reqwest::RequestBuilder,
+    ) -> Result<reqwest::Response, reqwest::Error> {
+        let (client, request) = builder.build_split();
+        let mut request = request?;
+        let method = request.method().to_string();
+        let path = request.url()[Position::AfterPort..].to_string();
+
+        let mut stored = self.cached_header(&method, &path);
+        let mut retried = false;
+        loop {
+            let pilot = client.request(request.method().clone(), request.url().clone());
+            let pilot = match &stored {
+                None => pilot.send().await?,
+                Some((_, value)) => pilot.header(AUTHORIZATION, value).send().await?,
+            };
+            if pilot.status() != StatusCode::UNAUTHORIZED {
+                break;
             }
+            let used_nonce = stored.as_ref().map(|(nonce, _)| nonce.as_str());
+            let next = match retried {
+                true => None,
+                false => self.respond_to_challenge(&pilot, &path, &method, used_nonce),
+            };
+            let Some((header, value)) = next else {
+                self.set_challenge(None);
+                return Ok(pilot);
+            };
+            stored = Some((header.nonce.clone(), value));
+            self.set_challenge(Some(header));
+            retried = true;
         }
+
+        // The pilot used up the nonce count, so compute a fresh response for the real request.
+        if stored.is_some() {
+            stored = self.cached_header(&method, &path);
+        }
+        if let Some(value) = stored.and_then(|(_, v)| HeaderValue::from_str(&v).ok()) {
+            request.headers_mut().insert(AUTHORIZATION, value);
+        }
+        let response = client.execute(request).await?;
+        if response.status() == StatusCode::UNAUTHORIZED {
+            self.set_challenge(None);
+        }
+        Ok(response)
     }
 }
 
@@ -156,6 +252,19 @@ impl Authentication {
             challenge: Arc::new(Mutex::new(None)),
         })
     }
+
+    async fn send(
+        &self,
+        builder: reqwest::RequestBuilder,
+    ) -> Result<reqwest::Response, reqwest::Error> {
+        match self {
+            Self::Basic { username, password } => {
+                builder.basic_auth(username, Some(&password.0)).send().await
+            }
+            Self::Digest(digest) => digest.send(builder).await,
+            Self::Anonymous => builder.send().await,
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -169,6 +278,9 @@ pub struct ClientBuilder {
     plain_port: Option<u16>,
     secure_port: Option<u16>,
     credentials: Option<Credentials>,
+    pinned_certificate: Option<CertificateFingerprint>,
+    session: bool,
+    scheme_policy: SchemePolicy,
     inner: reqwest::ClientBuilder,
 }
 
@@ -179,10 +291,17 @@ impl ClientBuilder {
             plain_port: None,
             secure_port: None,
             credentials: None,
+            pinned_certificate: None,
+            session: false,
+            scheme_policy: SchemePolicy::default(),
             inner: reqwest::Client::builder(),
         }
     }
 
+    /// Create a builder for the device described by the environment, if any.
+    ///
+    /// As with [`Self::new`], the scheme policy defaults to [`SchemePolicy::SecureOnly`];
+    /// set `AXIS_DEVICE_SCHEME_POLICY` to allow plain HTTP.
     pub fn from_dut() -> anyhow::Result<Option<Self>> {
         let Some(device) = rs4a_dut::Device::from_env()? else {
             return Ok(None);
@@ -194,17 +313,35 @@ impl ClientBuilder {
             http_port,
             https_port,
             ssh_port: _,
+            rtsp_port: _,
             https_self_signed,
+            https_fingerprint,
+            scheme_policy,
         } = device;
+        let scheme_policy = match scheme_policy {
+            None => SchemePolicy::default(),
+            Some(policy) => policy
+                .parse()
+                .context("AXIS_DEVICE_SCHEME_POLICY is not a valid scheme policy")?,
+        };
 
         debug!("Building client using username {username} from env");
-        Ok(Some(
-            ClientBuilder::new(host)
-                .plain_port(http_port)
-                .secure_port(https_port)
-                .username_password(&username, &password)
-                .with_inner(|b| b.danger_accept_invalid_certs(https_self_signed)),
-        ))
+        let builder = ClientBuilder::new(host)
+            .plain_port(http_port)
+            .secure_port(https_port)
+            .username_password(&username, &password)
+            .scheme_policy(scheme_policy);
+        Ok(Some(match https_fingerprint {
+            #[cfg(feature = "rustls-tls")]
+            Some(fingerprint) => builder.pin_certificate(
+                fingerprint
+                    .parse()
+                    .context("AXIS_DEVICE_HTTPS_FINGERPRINT is not a valid fingerprint")?,
+            ),
+            #[cfg(not(feature = "rustls-tls"))]
+            Some(_) => bail!("AXIS_DEVICE_HTTPS_FINGERPRINT requires the rustls-tls feature"),
+            None => builder.with_inner(|b| b.danger_accept_invalid_certs(https_self_signed)),
+        }))
     }
 
     pub fn username_password(mut self, username: &str, password: &str) -> Self {
@@ -225,6 +362,56 @@ impl ClientBuilder {
         self
     }
 
+    /// Trust certificates signed by the root CA in `pem`, in addition to the built-in roots.
+    pub fn root_ca_pem(mut self, pem: &[u8]) -> anyhow::Result<Self> {
+        let certificate =
+            reqwest::Certificate::from_pem(pem).context("Could not parse root CA certificate")?;
+        self.inner = self.inner.add_root_certificate(certificate);
+        Ok(self)
+    }
+
+    /// Trust only the certificate with the given fingerprint.
+    ///
+    /// Neither the issuer nor the hostname of the certificate is verified, so this works also for
+    /// self-signed certificates.
+    /// Use [`tls::fetch_certificate_fingerprint`] to get the fingerprint on first use.
+    ///
+    /// Takes precedence over [`Self::root_ca_pem`] and TLS options set using [`Self::with_inner`].
+    #[cfg(feature = "rustls-tls")]
+    pub fn pin_certificate(mut self, fingerprint: CertificateFingerprint) -> Self {
+        self.pinned_certificate = Some(fingerprint);
+        self
+    }
+
+    /// Reuse the session cookie set by the device instead of authenticating every request.
+    ///
+    /// When the session expires the client logs in again transparently.
+    /// Cookies marked `Secure` are sent only when using [`Scheme::Secure`].
+    /// Since Digest challenges are cached anyway, this does not save round trips per se, but the
+    /// device need not verify the credentials of every request.
+    /// Has no effect if the device does not set a cookie.
+    pub fn session(mut self, enabled: bool) -> Self {
+        self.session = enabled;
+        self
+    }
+
+    /// Which schemes [`Self::build`] may use; defaults to [`SchemePolicy::SecureOnly`].
+    pub fn scheme_policy(mut self, policy: SchemePolicy) -> Self {
+        self.scheme_policy = policy;
+        self
+    }
+
+    fn build_inner(
+        inner: reqwest::ClientBuilder,
+        pinned_certificate: Option<CertificateFingerprint>,
+    ) -> anyhow::Result<reqwest::Client> {
+        let inner = match pinned_certificate {
+            None => inner,
+            Some(fingerprint) => tls::pin(inner, fingerprint)?,
+        };
+        Ok(inner.build()?)
+    }
+
     pub fn with_inner(
         mut self,
         f: impl FnOnce(reqwest::ClientBuilder) -> reqwest::ClientBuilder,
@@ -239,9 +426,12 @@ impl ClientBuilder {
             plain_port,
             secure_port,
             credentials,
+            pinned_certificate,
+            session,
+            scheme_policy: _,
             inner,
         } = self;
-        let client = inner.build()?;
+        let client = Self::build_inner(inner, pinned_certificate)?;
         Ok(Client {
             auth: match credentials {
                 None => Authentication::Anonymous,
@@ -250,6 +440,7 @@ impl ClientBuilder {
                     false => Authentication::Basic { username, password },
                 },
             },
+            session: session.then(Session::default),
             scheme,
             host,
             port: match scheme {
@@ -262,56 +453,97 @@ impl ClientBuilder {
 
     /// Create a new client and automatically set authentication and scheme
     pub async fn build(self) -> anyhow::Result<Client> {
+        let (client, _) = self.build_with_report().await?;
+        Ok(client)
+    }
+
+    /// Like [`Self::build`] but also returns why any schemes were rejected.
+    ///
+    /// If no scheme can be used, the error is a [`SchemeError`] with the same report.
+    pub async fn build_with_report(self) -> anyhow::Result<(Client, SchemeReport)> {
         let Self {
             host,
             plain_port,
             secure_port,
             credentials,
+            pinned_certificate,
+            session,
+            scheme_policy,
             inner,
         } = self;
 
         let mut client = Client {
             auth: Authentication::Anonymous,
+            session: session.then(Session::default),
             scheme: Scheme::Secure,
             host,
             port: None,
-            client: inner.build()?,
+            client: Self::build_inner(inner, pinned_certificate)?,
         };
-        // If the certificate is self-signed and self-signed certificates are not allowed,
-        // then this will fall back on plain HTTP, which is probably worse.
-        // TODO: Consider skipping plain by default or falling through only on specific errors
-        let () = Self::set_scheme(
-            &mut client,
-            &[(Scheme::Secure, secure_port), (Scheme::Plain, plain_port)],
-        )
-        .await?;
+        let report = Self::set_scheme(&mut client, scheme_policy, secure_port, plain_port).await?;
         if let Some(credentials) = credentials {
             let () = Self::set_authentication(&mut client, credentials).await?;
         }
-        Ok(client)
+        Ok((client, report))
     }
 
-    async fn set_scheme(
+    async fn try_scheme(
         candidate: &mut Client,
-        schemes: &[(Scheme, Option<u16>)],
+        scheme: Scheme,
+        port: Option<u16>,
     ) -> anyhow::Result<()> {
-        for (scheme, port) in schemes {
-            candidate.scheme = *scheme;
-            candidate.port = *port;
-            if SystemReadyRequest::new()
-                .timeout(1)
-                .send(candidate)
-                .await
-                .inspect_err(|e| debug!("Could not connect using {} because {e:?}", scheme.http()))
-                .is_ok()
-            {
-                return Ok(());
+        candidate.scheme = scheme;
+        candidate.port = port;
+        let probe = RetryClient::new(
+            candidate.clone(),
+            RetryPolicy::never().timeout(SCHEME_PROBE_TIMEOUT),
+        );
+        SystemReadyRequest::new()
+            .send(&probe)
+            .await
+            .inspect_err(|e| debug!("Could not connect using {} because {e:?}", scheme.http()))?;
+        Ok(())
+    }
+
+    async fn set_scheme(
+        candidate: &mut Client,
+        policy: SchemePolicy,
+        secure_port: Option<u16>,
+        plain_port: Option<u16>,
+    ) -> Result<SchemeReport, SchemeError> {
+        let mut report = SchemeReport::default();
+        if policy != SchemePolicy::PlainOnly {
+            match Self::try_scheme(candidate, Scheme::Secure, secure_port).await {
+                Ok(()) => return Ok(report),
+                Err(e) => {
+                    // Other errors, such as an untrusted certificate, may be caused by an attacker
+                    // who would also be able to read anything sent in plain text.
+                    let fall_back =
+                        policy == SchemePolicy::PreferSecure && is_connection_refused(&e);
+                    report.rejected.push((Scheme::Secure, e));
+                    if !fall_back {
+                        return Err(SchemeError { report });
+                    }
+                }
+            }
+        }
+        match Self::try_scheme(candidate, Scheme::Plain, plain_port).await {
+            Ok(()) => {
+                if !report.rejected.is_empty() {
+                    warn!("Falling back to plain HTTP: {report}");
+                }
+                Ok(report)
+            }
+            Err(e) => {
+                report.rejected.push((Scheme::Plain, e));
+                Err(SchemeError { report })
             }
         }
-        Err(anyhow::anyhow!("Could not connect to either scheme"))
     }
 
-    // TODO: Consider handling multiple www_authenticate headers.
+    /// Pick the strongest authentication scheme offered by the server.
+    ///
+    /// Digest is preferred over Basic, and stronger Digest algorithms over weaker ones.
     async fn set_authentication(
         client: &mut Client,
         credentials: Credentials,
@@ -333,32 +565,110 @@ impl ClientBuilder {
 
         let Credentials { username, password } = credentials;
 
-        let www_auth = response
-            .headers()
-            .get(WWW_AUTHENTICATE)
-            .and_then(|x| x.to_str().ok())
-            .unwrap_or_default();
+        let challenges = challenge::parse(response.headers());
+        if challenges.is_empty() {
+            bail!("Server requires authentication but no methods were offered")
+        }
+        let offered = challenges
+            .iter()
+            .map(|c| c.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        debug!("Server offers authentication using {offered}");
 
-        match www_auth.to_lowercase().as_str() {
-            s if s.starts_with("basic ") => {
+        match challenge::strongest(challenges) {
+            Some(Challenge::Basic) => {
                 client.auth = Authentication::Basic { username, password };
             }
-            s if s.starts_with("digest ") => {
-                let header = digest_auth::parse(www_auth)?;
+            Some(Challenge::Digest(header)) => {
                 client.auth =
                     Authentication::Digest(DigestAuth::with_challenge(username, password, header));
             }
-            "" => {
-                bail!("Server requires authentication but no methods were offered")
+            Some(Challenge::Unsupported(_)) | None => {
+                bail!("None of the offered authentication methods are supported: {offered}")
             }
-            s => bail!("Authentication method not supported: {s}"),
         }
 
         Ok(())
     }
 }
 
-#[derive(Clone, Copy, Debug)]
+fn is_connection_refused(error: &anyhow::Error) -> bool {
+    error.chain().any(|e| {
+        e.downcast_ref::<std::io::Error>()
+            .is_some_and(|e| e.kind() == std::io::ErrorKind::ConnectionRefused)
+    })
+}
+
+/// Which schemes a client may use, and when it may fall back from HTTPS to HTTP.
+///
+/// Credentials are sent in plain text over HTTP, so it is used only if explicitly allowed.
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum SchemePolicy {
+    /// Use only HTTPS.
+    #[default]
+    SecureOnly,
+    /// Use only HTTP.
+    PlainOnly,
+    /// Use HTTPS, falling back to HTTP only if the HTTPS connection is refused.
+    ///
+    /// Other errors, such as untrusted certificates, do not cause a fall back.
+    PreferSecure,
+}
+
+impl Display for SchemePolicy {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::SecureOnly => write!(f, "secure-only"),
+            Self::PlainOnly => write!(f, "plain-only"),
+            Self::PreferSecure => write!(f, "prefer-secure"),
+        }
+    }
+}
+
+impl FromStr for SchemePolicy {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "secure-only" => Ok(Self::SecureOnly),
+            "plain-only" => Ok(Self::PlainOnly),
+            "prefer-secure" => Ok(Self::PreferSecure),
+            _ => bail!("Expected one of secure-only, plain-only or prefer-secure but got {s:?}"),
+        }
+    }
+}
+
+/// The schemes that were tried but rejected, and why.
+#[derive(Debug, Default)]
+pub struct SchemeReport {
+    pub rejected: Vec<(Scheme, anyhow::Error)>,
+}
+
+impl Display for SchemeReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.rejected.is_empty() {
+            return write!(f, "no scheme was rejected");
+        }
+        for (i, (scheme, error)) in self.rejected.iter().enumerate() {
+            if i > 0 {
+                write!(f, "; ")?;
+            }
+            write!(f, "{} was rejected because {error:#}", scheme.http())?;
+        }
+        Ok(())
+    }
+}
+
+/// None of the schemes allowed by the [`SchemePolicy`] could be used.
+#[derive(Debug, thiserror::Error)]
+#[error("Could not connect using any allowed scheme: {report}")]
+pub struct SchemeError {
+    pub report: SchemeReport,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum Scheme {
     /// HTTPS and WSS
     Secure,
@@ -380,6 +690,7 @@ impl Scheme {
 #[derive(Clone)]
 pub struct Client {
     auth: Authentication,
+    session: Option<Session>,
     scheme: Scheme,
     host: Host,
     port: Option<u16>,
@@ -401,7 +712,60 @@ impl Client {
 
     pub fn request(&self, method: Method, path: &str) -> anyhow::Result<RequestBuilder> {
         let builder = self.client.request(method, self.url().join(path)?);
-        Ok(RequestBuilder::new(self.auth.clone(), builder))
+        Ok(RequestBuilder::new(
+            self.auth.clone(),
+            self.session.clone(),
+            self.scheme,
+            builder,
+        ))
+    }
+
+    /// Open a WebSocket connection to `path`.
+    ///
+    /// The connection is established using the same scheme, TLS settings and authentication as
+    /// any other request made by this client.
+    pub async fn websocket(
+        &self,
+        path: &str,
+    ) -> anyhow::Result<WebSocketStream<reqwest::Upgraded>> {
+        let key = generate_key();
+        // The upgrade mechanism does not exist in HTTP/2.
+        let builder = self
+            .client
+            .get(self.url().join(path)?)
+            .version(Version::HTTP_11)
+            .header(CONNECTION, "Upgrade")
+            .header(UPGRADE, "websocket")
+            .header(SEC_WEBSOCKET_VERSION, "13")
+            .header(SEC_WEBSOCKET_KEY, &key);
+        let response = RequestBuilder::new(
+            self.auth.clone(),
+            self.session.clone(),
+            self.scheme,
+            builder,
+        )
+        .send()
+        .await
+        .context("Failed to send upgrade request")?;
+
+        let status = response.status();
+        ensure!(
+            status == StatusCode::SWITCHING_PROTOCOLS,
+            "Expected {} but got {status}",
+            StatusCode::SWITCHING_PROTOCOLS
+        );
+        let accept = response
+            .headers()
+            .get(SEC_WEBSOCKET_ACCEPT)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default();
+        ensure!(
+            accept == derive_accept_key(key.as_bytes()),
+            "Server did not accept the WebSocket key"
+        );
+
+        let upgraded = response.upgrade().await.context("Failed to upgrade")?;
+        Ok(WebSocketStream::from_raw_socket(upgraded, Role::Client, None).await)
     }
 
     fn url(&self) -> Url {
@@ -420,52 +784,64 @@ impl Client {
 
 pub struct RequestBuilder {
     auth: Authentication,
+    session: Option<Session>,
+    scheme: Scheme,
     builder: reqwest::RequestBuilder,
 }
 
 impl RequestBuilder {
-    fn new(auth: Authentication, builder: reqwest::RequestBuilder) -> Self {
-        Self { auth, builder }
+    fn new(
+        auth: Authentication,
+        session: Option<Session>,
+        scheme: Scheme,
+        builder: reqwest::RequestBuilder,
+    ) -> Self {
+        Self {
+            auth,
+            session,
+            scheme,
+            builder,
+        }
     }
 
     pub fn query<T: serde::Serialize + ?Sized>(self, query: &T) -> Self {
         Self {
-            auth: self.auth,
             builder: self.builder.query(query),
+            ..self
         }
     }
 
     pub fn header(self, key: reqwest::header::HeaderName, value: &str) -> Self {
         Self {
-            auth: self.auth,
             builder: self.builder.header(key, value),
+            ..self
         }
     }
 
     pub fn body<T: Into<reqwest::Body>>(self, body: T) -> Self {
         Self {
-            auth: self.auth,
             builder: self.builder.body(body),
+            ..self
         }
     }
 
     pub fn json<T: serde::Serialize + ?Sized>(self, json: &T) -> Self {
         Self {
-            auth: self.auth,
             builder: self.builder.json(json),
+            ..self
         }
     }
 
     pub async fn send(self) -> Result<reqwest::Response, reqwest::Error> {
-        match self.auth {
-            Authentication::Basic { username, password } => {
-                self.builder
-                    .basic_auth(&username, Some(&password.0))
-                    .send()
-                    .await
-            }
-            Authentication::Digest(digest) => digest.send(self.builder).await,
-            Authentication::Anonymous => self.builder.send().await,
+        let Self {
+            auth,
+            session,
+            scheme,
+            builder,
+        } = self;
+        match session {
+            None => auth.send(builder).await,
+            Some(session) => session.send(&auth, scheme, builder).await,
         }
     }
 }
@@ -473,9 +849,18 @@ impl RequestBuilder {
 impl HttpClient for Client {
     async fn execute(&self, request: Request) -> Result<Response, anyhow::Error> {
         let mut request_builder = self.request(request.method, &request.path)?;
-        if let Some(body) = request.body {
-            debug_assert!(request.content_type.is_some());
-            request_builder = request_builder.body(body);
+        match request.body {
+            None => {}
+            Some(Body::Bytes(body)) => {
+                debug_assert!(request.content_type.is_some());
+                request_builder = request_builder.body(body);
+            }
+            Some(Body::Stream(body)) => {
+                debug_assert!(request.content_type.is_some());
+                request_builder = request_builder
+                    .header(reqwest::header::CONTENT_LENGTH, &body.len().to_string())
+                    .body(reqwest::Body::wrap_stream(body.into_stream()));
+            }
         }
         if let Some(content_type) = request.content_type {
             request_builder = request_builder.header(reqwest::header::CONTENT_TYPE, &content_type);
@@ -487,3 +872,162 @@ impl HttpClient for Client {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use reqwest::header::CONTENT_LENGTH;
+    use test_server::{RecordedRequest, Reply, TestServer};
+
+    use super::*;
+
+    /// Accept Digest responses from `root` to the current nonce, which the test may change.
+    fn digest_handler(
+        nonce: Arc<Mutex<String>>,
+        stale: bool,
+    ) -> impl Fn(&RecordedRequest) -> Reply + Send + Sync + 'static {
+        move |request| {
+            let nonce = nonce.lock().unwrap().clone();
+            let authorization = request.header("authorization");
+            if authorization.is_some_and(|a| {
+                a.contains(r#"username="root""#) && a.contains(&format!(r#"nonce="{nonce}""#))
+            }) {
+                return (200, Vec::new());
+            }
+            let stale = match stale && authorization.is_some() {
+                true => ", stale=true",
+                false => "",
+            };
+            let challenge = format!(
+                r#"Digest realm="test", nonce="{nonce}", qop="auth", algorithm=SHA-256{stale}"#
+            );
+            (401, vec![("WWW-Authenticate", challenge)])
+        }
+    }
+
+    fn digest_client(port: u16, username: &str) -> Client {
+        ClientBuilder::new(Host::parse("127.0.0.1").unwrap())
+            .plain_port(Some(port))
+            .username_password(username, "pass")
+            .build_with_scheme(Scheme::Plain, true)
+            .unwrap()
+    }
+
+    async fn upload(client: &Client) -> StatusCode {
+        let body = futures_util::stream::once(async { Ok::<_, std::io::Error>(b"hello".to_vec()) });
+        client
+            .post("upload")
+            .unwrap()
+            .header(CONTENT_LENGTH, "5")
+            .body(reqwest::Body::wrap_stream(body))
+            .send()
+            .await
+            .unwrap()
+            .status()
+    }
+
+    fn upload_bodies(server: &TestServer) -> Vec<Vec<u8>> {
+        server
+            .requests()
+            .into_iter()
+            .filter(|r| r.path == "/upload")
+            .inspect(|r| assert_eq!(r.method, "POST"))
+            .map(|r| r.body)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn retries_once_when_nonce_is_replaced() {
+        let nonce = Arc::new(Mutex::new("a".to_string()));
+        let server = TestServer::start(digest_handler(Arc::clone(&nonce), false)).await;
+        let client = digest_client(server.port, "root");
+        let response = client.get("ping").unwrap().send().await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // Restarting a device replaces the nonce without marking the old one stale.
+        *nonce.lock().unwrap() = "b".to_string();
+        let response = client.get("ping").unwrap().send().await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let authenticated: Vec<_> = server
+            .requests()
+            .iter()
+            .map(|r| r.header("authorization").is_some())
+            .collect();
+        assert_eq!(authenticated, [false, true, true, true]);
+
+        let client = digest_client(server.port, "mallory");
+        let response = client.get("ping").unwrap().send().await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(server.requests().len(), 6);
+        let Authentication::Digest(digest) = &client.auth else {
+            panic!("Expected Digest authentication");
+        };
+        assert!(digest.challenge.lock().unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn streamed_body_is_sent_once_nonce_is_accepted() {
+        let nonce = Arc::new(Mutex::new("a".to_string()));
+        let server = TestServer::start(digest_handler(Arc::clone(&nonce), true)).await;
+        let client = digest_client(server.port, "root");
+        let response = client.get("ping").unwrap().send().await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        *nonce.lock().unwrap() = "b".to_string();
+        assert_eq!(upload(&client).await, StatusCode::OK);
+        assert_eq!(
+            upload_bodies(&server),
+            [b"".to_vec(), b"".to_vec(), b"hello".to_vec()]
+        );
+    }
+
+    #[tokio::test]
+    async fn streamed_body_is_not_sent_with_rejected_credentials() {
+        let nonce = Arc::new(Mutex::new("a".to_string()));
+        let server = TestServer::start(digest_handler(nonce, false)).await;
+        let client = digest_client(server.port, "mallory");
+        assert_eq!(upload(&client).await, StatusCode::UNAUTHORIZED);
+        assert_eq!(upload_bodies(&server), [b"".to_vec(), b"".to_vec()]);
+        let Authentication::Digest(digest) = &client.auth else {
+            panic!("Expected Digest authentication");
+        };
+        assert!(digest.challenge.lock().unwrap().is_none());
+    }
+
+    fn closed_port() -> u16 {
+        std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port()
+    }
+
+    async fn rejected_schemes(policy: SchemePolicy) -> Vec<Scheme> {
+        let port = closed_port();
+        let error = ClientBuilder::new(Host::parse("127.0.0.1").unwrap())
+            .plain_port(Some(port))
+            .secure_port(Some(port))
+            .scheme_policy(policy)
+            .build()
+            .await
+            .err()
+            .unwrap();
+        let error = error.downcast::<SchemeError>().unwrap();
+        error.report.rejected.iter().map(|(s, _)| *s).collect()
+    }
+
+    #[tokio::test]
+    async fn falls_back_to_plain_only_if_allowed() {
+        assert_eq!(
+            rejected_schemes(SchemePolicy::SecureOnly).await,
+            [Scheme::Secure]
+        );
+        assert_eq!(
+            rejected_schemes(SchemePolicy::PlainOnly).await,
+            [Scheme::Plain]
+        );
+        assert_eq!(
+            rejected_schemes(SchemePolicy::PreferSecure).await,
+            [Scheme::Secure, Scheme::Plain]
+        );
+    }
+}
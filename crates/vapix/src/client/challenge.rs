@@ -0,0 +1,216 @@
+//! Parsing of the challenges offered in `WWW-Authenticate` headers.
+//!
+//! A response may include several headers, and each header may include several challenges, e.g.
+//! `Digest realm="a", nonce="b", algorithm=SHA-256, Digest realm="a", nonce="c", Basic realm="a"`.
+use std::fmt::{Display, Formatter};
+
+use digest_auth::{AlgorithmType, WwwAuthenticateHeader};
+use log::debug;
+use reqwest::header::{HeaderMap, WWW_AUTHENTICATE};
+
+#[derive(Clone, Debug)]
+pub(super) enum Challenge {
+    Basic,
+    Digest(WwwAuthenticateHeader),
+    /// A scheme that is not supported, or a supported scheme that could not be parsed.
+    Unsupported(String),
+}
+
+impl Display for Challenge {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Basic => write!(f, "Basic"),
+            Self::Digest(header) => write!(f, "Digest ({})", header.algorithm),
+            Self::Unsupported(scheme) => write!(f, "{scheme}"),
+        }
+    }
+}
+
+impl Challenge {
+    /// Rank supported challenges so that stronger challenges are preferred.
+    fn strength(&self) -> Option<u8> {
+        match self {
+            Self::Basic => Some(0),
+            Self::Digest(header) => match header.algorithm.algo {
+                AlgorithmType::MD5 => Some(1),
+                AlgorithmType::SHA2_256 => Some(2),
+                AlgorithmType::SHA2_512_256 => Some(3),
+            },
+            Self::Unsupported(_) => None,
+        }
+    }
+}
+
+/// Split a header value into `(scheme, params)` pairs.
+fn split(value: &str) -> Vec<(String, String)> {
+    let mut items = Vec::new();
+    let mut item = String::new();
+    let mut quoted = false;
+    let mut escaped = false;
+    for c in value.chars() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if quoted => escaped = true,
+            '"' => quoted = !quoted,
+            ',' if !quoted => {
+                items.push(std::mem::take(&mut item));
+                continue;
+            }
+            _ => {}
+        }
+        item.push(c);
+    }
+    items.push(item);
+
+    let mut challenges: Vec<(String, Vec<String>)> = Vec::new();
+    for item in items.iter().map(|i| i.trim()).filter(|i| !i.is_empty()) {
+        let space = item.find(char::is_whitespace);
+        let equals = item.find('=');
+        let starts_challenge = match (space, equals) {
+            (_, None) => true,
+            (Some(space), Some(equals)) => space < equals,
+            (None, Some(_)) => false,
+        };
+        if starts_challenge {
+            let (scheme, param) = item.split_once(char::is_whitespace).unwrap_or((item, ""));
+            let params = match param.trim() {
+                "" => Vec::new(),
+                param => vec![param.to_string()],
+            };
+            challenges.push((scheme.to_string(), params));
+        } else if let Some((_, params)) = challenges.last_mut() {
+            params.push(item.to_string());
+        } else {
+            debug!("Ignoring auth-param without a scheme: {item}");
+        }
+    }
+    challenges
+        .into_iter()
+        .map(|(scheme, params)| (scheme, params.join(", ")))
+        .collect()
+}
+
+/// Returns every challenge offered in `headers`, in the order they were offered.
+pub(super) fn parse(headers: &HeaderMap) -> Vec<Challenge> {
+    let mut challenges = Vec::new();
+    for value in headers.get_all(WWW_AUTHENTICATE) {
+        let Ok(value) = value.to_str() else {
+            debug!("WWW-Authenticate header is not valid UTF-8");
+            continue;
+        };
+        for (scheme, params) in split(value) {
+            challenges.push(match scheme.to_ascii_lowercase().as_str() {
+                "basic" => Challenge::Basic,
+                "digest" => match digest_auth::parse(&params) {
+                    Ok(header) => Challenge::Digest(header),
+                    Err(e) => {
+                        debug!("Failed to parse Digest challenge: {e}");
+                        Challenge::Unsupported(scheme)
+                    }
+                },
+                _ => Challenge::Unsupported(scheme),
+            });
+        }
+    }
+    challenges
+}
+
+/// Returns the strongest supported challenge, preferring the first offered among equals.
+pub(super) fn strongest(challenges: Vec<Challenge>) -> Option<Challenge> {
+    let mut best: Option<(u8, Challenge)> = None;
+    for challenge in challenges {
+        let Some(strength) = challenge.strength() else {
+            continue;
+        };
+        if best.as_ref().is_none_or(|(s, _)| *s < strength) {
+            best = Some((strength, challenge));
+        }
+    }
+    best.map(|(_, c)| c)
+}
+
+/// Returns the strongest Digest challenge offered in `headers`.
+pub(super) fn strongest_digest(headers: &HeaderMap) -> Option<WwwAuthenticateHeader> {
+    let digests = parse(headers)
+        .into_iter()
+        .filter(|c| matches!(c, Challenge::Digest(_)))
+        .collect();
+    match strongest(digests)? {
+        Challenge::Digest(header) => Some(header),
+        Challenge::Basic | Challenge::Unsupported(_) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::header::HeaderValue;
+
+    use super::*;
+
+    fn headers(values: &[&'static str]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for value in values {
+            headers.append(WWW_AUTHENTICATE, HeaderValue::from_static(value));
+        }
+        headers
+    }
+
+    #[test]
+    fn can_split_multiple_challenges_in_one_header() {
+        let challenges = split(
+            r#"Digest realm="AXIS, Inc", nonce="a", algorithm=MD5, qop="auth,auth-int", Negotiate YWJj==, Basic realm="AXIS""#,
+        );
+        assert_eq!(
+            challenges,
+            [
+                (
+                    "Digest".to_string(),
+                    r#"realm="AXIS, Inc", nonce="a", algorithm=MD5, qop="auth,auth-int""#
+                        .to_string()
+                ),
+                ("Negotiate".to_string(), "YWJj==".to_string()),
+                ("Basic".to_string(), r#"realm="AXIS""#.to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn prefers_sha256_over_md5_over_basic() {
+        let challenges = parse(&headers(&[
+            r#"Basic realm="AXIS""#,
+            r#"Digest realm="AXIS", nonce="a", algorithm=MD5, qop="auth""#,
+            r#"Digest realm="AXIS", nonce="b", algorithm=SHA-256, qop="auth""#,
+            r#"Negotiate"#,
+        ]));
+        assert_eq!(challenges.len(), 4);
+        let Some(Challenge::Digest(header)) = strongest(challenges) else {
+            panic!("Expected a Digest challenge");
+        };
+        assert_eq!(header.algorithm.algo, AlgorithmType::SHA2_256);
+        assert_eq!(header.nonce, "b");
+
+        let challenges = parse(&headers(&[
+            r#"Basic realm="AXIS", Digest realm="AXIS", nonce="a", qop="auth""#,
+        ]));
+        let Some(Challenge::Digest(header)) = strongest(challenges) else {
+            panic!("Expected a Digest challenge");
+        };
+        assert_eq!(header.algorithm.algo, AlgorithmType::MD5);
+
+        let challenges = parse(&headers(&[r#"Basic realm="AXIS""#, "Negotiate"]));
+        assert!(matches!(strongest(challenges), Some(Challenge::Basic)));
+
+        let challenges = parse(&headers(&["Negotiate"]));
+        assert!(strongest(challenges).is_none());
+    }
+
+    #[test]
+    fn can_parse_stale_digest_challenge() {
+        let header = strongest_digest(&headers(&[
+            r#"Basic realm="AXIS""#,
+            r#"Digest realm="AXIS", nonce="c", algorithm=SHA-256, qop="auth", stale=TRUE"#,
+        ]))
+        .unwrap();
+        assert!(header.stale);
+    }
+}
@@ -0,0 +1,246 @@
+//! Reuse of session cookies to avoid authenticating every request.
+use std::sync::{Arc, Mutex};
+
+use log::debug;
+use reqwest::{
+    header::{HeaderMap, COOKIE, SET_COOKIE},
+    StatusCode,
+};
+
+use super::{Authentication, Scheme, Secret};
+
+#[derive(Clone, Debug)]
+struct Cookie {
+    name: String,
+    value: Secret,
+    /// The cookie must be sent only over secure connections.
+    secure: bool,
+}
+
+/// Update `cookies` with every cookie set in `headers`.
+///
+/// Cookies set to an empty value are removed.
+fn merge_cookies(cookies: &mut Vec<Cookie>, headers: &HeaderMap) {
+    for set_cookie in headers
+        .get_all(SET_COOKIE)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+    {
+        let mut parts = set_cookie.split(';');
+        let Some((name, value)) = parts.next().and_then(|p| p.split_once('=')) else {
+            continue;
+        };
+        let (name, value) = (name.trim(), value.trim());
+        let secure = parts.any(|a| a.trim().eq_ignore_ascii_case("secure"));
+        cookies.retain(|c| c.name != name);
+        if !value.is_empty() {
+            cookies.push(Cookie {
+                name: name.to_string(),
+                value: Secret(value.to_string()),
+                secure,
+            });
+        }
+    }
+}
+
+/// Returns a `Cookie` header with the cookies that may be sent using `scheme`, if any.
+fn cookie_header(cookies: &[Cookie], scheme: Scheme) -> Option<String> {
+    let pairs: Vec<_> = cookies
+        .iter()
+        .filter(|c| scheme == Scheme::Secure || !c.secure)
+        .map(|c| format!("{}={}", c.name, c.value.0))
+        .collect();
+    match pairs.is_empty() {
+        true => None,
+        false => Some(pairs.join("; ")),
+    }
+}
+
+/// The session cookies shared by all clones of a client.
+#[derive(Clone, Debug, Default)]
+pub(super) struct Session(Arc<Mutex<Vec<Cookie>>>);
+
+impl Session {
+    fn cookie(&self, scheme: Scheme) -> Option<String> {
+        cookie_header(&self.0.lock().unwrap_or_else(|e| e.into_inner()), scheme)
+    }
+
+    fn clear(&self) {
+        self.0.lock().unwrap_or_else(|e| e.into_inner()).clear();
+    }
+
+    fn update(&self, response: &reqwest::Response) {
+        if response.status() == StatusCode::UNAUTHORIZED {
+            return;
+        }
+        merge_cookies(
+            &mut self.0.lock().unwrap_or_else(|e| e.into_inner()),
+            response.headers(),
+        );
+    }
+
+    /// Send the request using the session cookie, if any, and log in using `auth` otherwise.
+    ///
+    /// Cookies marked `Secure` are not sent when `scheme` is [`Scheme::Plain`].
+    /// If the session has expired the request is sent again using `auth`, unless the body can be
+    /// sent only once.
+    pub(super) async fn send(
+        &self,
+        auth: &Authentication,
+        scheme: Scheme,
+        builder: reqwest::RequestBuilder,
+    ) -> Result<reqwest::Response, reqwest::Error> {
+        let response = match self.cookie(scheme) {
+            None => auth.send(builder).await?,
+            Some(cookie) => {
+                let retry = builder.try_clone();
+                let response = builder.header(COOKIE, cookie).send().await?;
+                if response.status() != StatusCode::UNAUTHORIZED {
+                    self.update(&response);
+                    return Ok(response);
+                }
+                debug!("Session has expired, logging in again");
+                self.clear();
+                match retry {
+                    None => return Ok(response),
+                    Some(retry) => auth.send(retry).await?,
+                }
+            }
+        };
+        self.update(&response);
+        Ok(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::header::HeaderValue;
+    use url::Host;
+
+    use super::{
+        super::{
+            test_server::{RecordedRequest, Reply, TestServer},
+            Client, ClientBuilder,
+        },
+        *,
+    };
+
+    #[test]
+    fn can_merge_cookies() {
+        let mut cookies = Vec::new();
+        merge_cookies(&mut cookies, &HeaderMap::new());
+        assert_eq!(cookie_header(&cookies, Scheme::Secure), None);
+
+        let mut headers = HeaderMap::new();
+        headers.append(
+            SET_COOKIE,
+            HeaderValue::from_static("sessionId=abc123; Path=/; Secure; HttpOnly"),
+        );
+        headers.append(SET_COOKIE, HeaderValue::from_static("expired=; Max-Age=0"));
+        headers.append(SET_COOKIE, HeaderValue::from_static("csrf=def"));
+        merge_cookies(&mut cookies, &headers);
+        assert_eq!(
+            cookie_header(&cookies, Scheme::Secure).as_deref(),
+            Some("sessionId=abc123; csrf=def")
+        );
+        assert_eq!(
+            cookie_header(&cookies, Scheme::Plain).as_deref(),
+            Some("csrf=def")
+        );
+
+        let mut headers = HeaderMap::new();
+        headers.append(SET_COOKIE, HeaderValue::from_static("csrf=ghi"));
+        headers.append(
+            SET_COOKIE,
+            HeaderValue::from_static("sessionId=; Max-Age=0"),
+        );
+        merge_cookies(&mut cookies, &headers);
+        assert_eq!(
+            cookie_header(&cookies, Scheme::Secure).as_deref(),
+            Some("csrf=ghi")
+        );
+    }
+
+    /// Accept the current session cookie or, failing that, Basic authentication as `root`.
+    ///
+    /// Successful logins set a new session cookie with the given `attributes`.
+    fn session_handler(
+        current: Arc<Mutex<u32>>,
+        attributes: &'static str,
+    ) -> impl Fn(&RecordedRequest) -> Reply + Send + Sync + 'static {
+        move |request| {
+            let mut current = current.lock().unwrap();
+            let session = format!("session={current}");
+            if request.header("cookie") == Some(session.as_str()) {
+                return (200, Vec::new());
+            }
+            // `root:pass`
+            if request.header("authorization") != Some("Basic cm9vdDpwYXNz") {
+                return (
+                    401,
+                    vec![("WWW-Authenticate", r#"Basic realm="test""#.into())],
+                );
+            }
+            *current += 1;
+            let cookie = format!("session={current}{attributes}");
+            (200, vec![("Set-Cookie", cookie)])
+        }
+    }
+
+    fn session_client(port: u16) -> Client {
+        ClientBuilder::new(Host::parse("127.0.0.1").unwrap())
+            .plain_port(Some(port))
+            .username_password("root", "pass")
+            .session(true)
+            .build_with_scheme(Scheme::Plain, false)
+            .unwrap()
+    }
+
+    async fn ping(client: &Client) -> StatusCode {
+        client.get("ping").unwrap().send().await.unwrap().status()
+    }
+
+    fn sent_cookies(server: &TestServer) -> Vec<Option<String>> {
+        server
+            .requests()
+            .iter()
+            .map(|r| r.header("cookie").map(str::to_string))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn logs_in_again_when_session_expires() {
+        let current = Arc::new(Mutex::new(0));
+        let server = TestServer::start(session_handler(Arc::clone(&current), "; Path=/")).await;
+        let client = session_client(server.port);
+        assert_eq!(ping(&client).await, StatusCode::OK);
+        assert_eq!(ping(&client).await, StatusCode::OK);
+
+        // Expire the session.
+        *current.lock().unwrap() += 1;
+        assert_eq!(ping(&client).await, StatusCode::OK);
+        assert_eq!(ping(&client).await, StatusCode::OK);
+
+        let session = |n: u32| Some(format!("session={n}"));
+        assert_eq!(
+            sent_cookies(&server),
+            [None, session(1), session(1), None, session(3)]
+        );
+        let requests = server.requests();
+        let logins: Vec<_> = requests
+            .iter()
+            .map(|r| r.header("authorization").is_some())
+            .collect();
+        assert_eq!(logins, [true, false, false, true, false]);
+    }
+
+    #[tokio::test]
+    async fn does_not_send_secure_cookie_over_plain_http() {
+        let current = Arc::new(Mutex::new(0));
+        let server = TestServer::start(session_handler(current, "; Secure; HttpOnly")).await;
+        let client = session_client(server.port);
+        assert_eq!(ping(&client).await, StatusCode::OK);
+        assert_eq!(ping(&client).await, StatusCode::OK);
+        assert_eq!(sent_cookies(&server), [None, None]);
+    }
+}
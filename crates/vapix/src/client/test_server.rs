@@ -0,0 +1,125 @@
+//! A minimal HTTP server that answers requests using a handler and records them for assertions.
+use std::sync::{Arc, Mutex};
+
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+};
+
+#[derive(Clone, Debug)]
+pub(super) struct RecordedRequest {
+    pub method: String,
+    pub path: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl RecordedRequest {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+/// The status and headers to respond with; responses have no body.
+pub(super) type Reply = (u16, Vec<(&'static str, String)>);
+
+type Handler = Arc<dyn Fn(&RecordedRequest) -> Reply + Send + Sync>;
+
+pub(super) struct TestServer {
+    pub port: u16,
+    requests: Arc<Mutex<Vec<RecordedRequest>>>,
+}
+
+impl TestServer {
+    pub async fn start(
+        handler: impl Fn(&RecordedRequest) -> Reply + Send + Sync + 'static,
+    ) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let handler: Handler = Arc::new(handler);
+        tokio::spawn({
+            let requests = Arc::clone(&requests);
+            async move {
+                while let Ok((stream, _)) = listener.accept().await {
+                    let requests = Arc::clone(&requests);
+                    let handler = Arc::clone(&handler);
+                    tokio::spawn(async move {
+                        if let Err(e) = serve(stream, handler, requests).await {
+                            eprintln!("Test server failed to serve connection: {e:?}");
+                        }
+                    });
+                }
+            }
+        });
+        Self { port, requests }
+    }
+
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|w| w == needle)
+}
+
+async fn serve(
+    mut stream: TcpStream,
+    handler: Handler,
+    requests: Arc<Mutex<Vec<RecordedRequest>>>,
+) -> anyhow::Result<()> {
+    let mut buffer = Vec::new();
+    let mut chunk = [0; 4096];
+    let headers_end = loop {
+        if let Some(i) = find(&buffer, b"\r\n\r\n") {
+            break i;
+        }
+        let n = stream.read(&mut chunk).await?;
+        anyhow::ensure!(n > 0, "Connection closed before headers were complete");
+        buffer.extend_from_slice(chunk.get(..n).unwrap_or_default());
+    };
+    let head = String::from_utf8(buffer.get(..headers_end).unwrap_or_default().to_vec())?;
+    let mut lines = head.lines();
+    let request_line = lines.next().unwrap_or_default();
+    let mut parts = request_line.split(' ');
+    let method = parts.next().unwrap_or_default().to_string();
+    let path = parts.next().unwrap_or_default().to_string();
+    let headers: Vec<_> = lines
+        .filter_map(|l| l.split_once(':'))
+        .map(|(n, v)| (n.trim().to_string(), v.trim().to_string()))
+        .collect();
+    let content_length = headers
+        .iter()
+        .find(|(n, _)| n.eq_ignore_ascii_case("content-length"))
+        .map(|(_, v)| v.parse::<usize>())
+        .transpose()?
+        .unwrap_or(0);
+    let mut body = buffer.split_off(headers_end + 4);
+    while body.len() < content_length {
+        let n = stream.read(&mut chunk).await?;
+        anyhow::ensure!(n > 0, "Connection closed before body was complete");
+        body.extend_from_slice(chunk.get(..n).unwrap_or_default());
+    }
+
+    let request = RecordedRequest {
+        method,
+        path,
+        headers,
+        body,
+    };
+    let (status, reply_headers) = handler(&request);
+    requests.lock().unwrap().push(request);
+
+    let mut response = format!("HTTP/1.1 {status} Test\r\n");
+    for (name, value) in reply_headers {
+        response.push_str(&format!("{name}: {value}\r\n"));
+    }
+    response.push_str("Content-Length: 0\r\nConnection: close\r\n\r\n");
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await?;
+    Ok(())
+}
@@ -1,15 +1,203 @@
 //! Client independent utilities for VAPIX HTTP integration
 
-use std::future::Future;
+use std::{
+    fmt::{Debug, Formatter},
+    future::Future,
+    io,
+    path::PathBuf,
+    pin::Pin,
+};
 
+use anyhow::Context;
+use futures_util::{future, stream, Stream, StreamExt};
 use reqwest::{Method, StatusCode};
+use tokio::{
+    fs::File,
+    io::{AsyncRead, AsyncReadExt},
+};
+
+mod retry;
+mod trace;
+
+pub use retry::{is_json_rpc_busy, RetryClient, RetryPolicy};
+pub use trace::{redact, Exchange, TracingClient};
+
+/// A request body that is produced incrementally, e.g. while reading a large file.
+pub struct BodyStream {
+    len: u64,
+    inner: Pin<Box<dyn Stream<Item = std::io::Result<Vec<u8>>> + Send>>,
+}
+
+impl BodyStream {
+    /// Create a body from a `stream` that yields exactly `len` bytes.
+    ///
+    /// The length is sent as the `Content-Length` since not all VAPIX endpoints accept chunked
+    /// transfer encoding.
+    pub fn new(
+        len: u64,
+        stream: impl Stream<Item = std::io::Result<Vec<u8>>> + Send + 'static,
+    ) -> Self {
+        Self {
+            len,
+            inner: Box::pin(stream),
+        }
+    }
+
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn into_stream(self) -> Pin<Box<dyn Stream<Item = std::io::Result<Vec<u8>>> + Send>> {
+        self.inner
+    }
+
+    /// Create a body of `head`, followed by exactly `len` bytes read from `reader`, and `tail`.
+    ///
+    /// The `progress` callback, if any, is called after every chunk read from `reader`.
+    pub fn from_reader(
+        head: Vec<u8>,
+        reader: Pin<Box<dyn AsyncRead + Send>>,
+        len: u64,
+        tail: Vec<u8>,
+        progress: Option<ProgressCallback>,
+    ) -> anyhow::Result<Self> {
+        let body_len = u64::try_from(head.len() + tail.len())?
+            .checked_add(len)
+            .context("upload is too large")?;
+
+        let content = stream::try_unfold(
+            (reader, 0u64, progress),
+            move |(mut reader, sent, mut progress)| async move {
+                let mut chunk = vec![0; CHUNK_SIZE];
+                let n = reader.read(&mut chunk).await?;
+                if n == 0 {
+                    if sent != len {
+                        return Err(io::Error::new(
+                            io::ErrorKind::UnexpectedEof,
+                            format!("expected {len} bytes but got {sent}"),
+                        ));
+                    }
+                    return Ok(None);
+                }
+                chunk.truncate(n);
+                let sent = sent.saturating_add(u64::try_from(n).unwrap_or(u64::MAX));
+                if sent > len {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("expected {len} bytes but got more"),
+                    ));
+                }
+                if let Some(progress) = progress.as_mut() {
+                    progress(sent, len);
+                }
+                Ok(Some((chunk, (reader, sent, progress))))
+            },
+        );
+
+        Ok(Self::new(
+            body_len,
+            stream::once(future::ready(Ok(head)))
+                .chain(content)
+                .chain(stream::once(future::ready(Ok(tail)))),
+        ))
+    }
+}
+
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// Called with the number of bytes sent so far and the total number of bytes.
+pub type ProgressCallback = Box<dyn FnMut(u64, u64) + Send>;
+
+/// The content of an upload, such as a firmware image or an application package.
+pub(crate) enum Upload {
+    Bytes(Vec<u8>),
+    Reader {
+        reader: Pin<Box<dyn AsyncRead + Send>>,
+        len: u64,
+    },
+    File(PathBuf),
+}
+
+impl Upload {
+    pub(crate) fn reader(reader: impl AsyncRead + Send + 'static, len: u64) -> Self {
+        Self::Reader {
+            reader: Box::pin(reader),
+            len,
+        }
+    }
+
+    /// Returns `request` with a multipart body of `head`, the content and `tail`.
+    ///
+    /// The content is streamed unless it is already in memory, in which case `progress` is not
+    /// called.
+    pub(crate) async fn into_multipart(
+        self,
+        request: Request,
+        boundary: &str,
+        head: Vec<u8>,
+        tail: Vec<u8>,
+        progress: Option<ProgressCallback>,
+    ) -> anyhow::Result<Request> {
+        let (reader, len): (Pin<Box<dyn AsyncRead + Send>>, u64) = match self {
+            Self::Bytes(content) => {
+                let mut body = head;
+                body.reserve_exact(content.len() + tail.len());
+                body.extend_from_slice(&content);
+                body.extend_from_slice(&tail);
+                return Ok(request.multipart(body, boundary));
+            }
+            Self::Reader { reader, len } => (reader, len),
+            Self::File(path) => {
+                let file = File::open(&path)
+                    .await
+                    .with_context(|| format!("Could not open {path:?}"))?;
+                let len = file
+                    .metadata()
+                    .await
+                    .with_context(|| format!("Could not read metadata of {path:?}"))?
+                    .len();
+                (Box::pin(file), len)
+            }
+        };
+        let body = BodyStream::from_reader(head, reader, len, tail, progress)?;
+        Ok(request.multipart_stream(body, boundary))
+    }
+}
+
+impl Debug for BodyStream {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BodyStream")
+            .field("len", &self.len)
+            .finish_non_exhaustive()
+    }
+}
+
+#[derive(Debug)]
+pub enum Body {
+    Bytes(Vec<u8>),
+    Stream(BodyStream),
+}
+
+impl Body {
+    /// Returns the content of the body, unless it is streamed.
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        match self {
+            Self::Bytes(bytes) => Some(bytes),
+            Self::Stream(_) => None,
+        }
+    }
+}
 
 #[non_exhaustive]
 #[derive(Debug)]
 pub struct Request {
     pub method: Method,
     pub path: String,
-    pub body: Option<Vec<u8>>,
+    pub body: Option<Body>,
     pub content_type: Option<String>,
 }
 
@@ -25,21 +213,42 @@ impl Request {
 
     pub fn json(mut self, body: String) -> Self {
         self.content_type = Some("application/json".to_string());
-        self.body = Some(body.into_bytes());
+        self.body = Some(Body::Bytes(body.into_bytes()));
         self
     }
 
     pub fn soap(mut self, body: String) -> Self {
         self.content_type = Some("application/soap+xml; charset=utf-8".to_string());
-        self.body = Some(body.into_bytes());
+        self.body = Some(Body::Bytes(body.into_bytes()));
         self
     }
 
     pub fn multipart(mut self, body: Vec<u8>, boundary: &str) -> Self {
         self.content_type = Some(format!("multipart/form-data; boundary={boundary}"));
-        self.body = Some(body);
+        self.body = Some(Body::Bytes(body));
         self
     }
+
+    pub fn multipart_stream(mut self, body: BodyStream, boundary: &str) -> Self {
+        self.content_type = Some(format!("multipart/form-data; boundary={boundary}"));
+        self.body = Some(Body::Stream(body));
+        self
+    }
+
+    /// Returns a copy of the request, unless the body is streamed.
+    pub fn try_clone(&self) -> Option<Self> {
+        let body = match &self.body {
+            None => None,
+            Some(Body::Bytes(bytes)) => Some(Body::Bytes(bytes.clone())),
+            Some(Body::Stream(_)) => return None,
+        };
+        Some(Self {
+            method: self.method.clone(),
+            path: self.path.clone(),
+            body,
+            content_type: self.content_type.clone(),
+        })
+    }
 }
 
 #[derive(Debug)]
@@ -54,3 +263,101 @@ pub trait HttpClient {
         request: Request,
     ) -> impl Future<Output = Result<Response, anyhow::Error>> + Send;
 }
+
+impl<T: HttpClient + Sync> HttpClient for &T {
+    fn execute(
+        &self,
+        request: Request,
+    ) -> impl Future<Output = Result<Response, anyhow::Error>> + Send {
+        (**self).execute(request)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+
+    async fn collect(body: BodyStream) -> Result<Vec<u8>, io::Error> {
+        let mut content = Vec::new();
+        let mut stream = body.into_stream();
+        while let Some(chunk) = stream.next().await {
+            content.extend(chunk?);
+        }
+        Ok(content)
+    }
+
+    #[tokio::test]
+    async fn body_from_reader_reports_progress() {
+        let content: Vec<u8> = (0..=255).cycle().take(CHUNK_SIZE * 2 + 7).collect();
+        let len = u64::try_from(content.len()).unwrap();
+
+        let reports = Arc::new(Mutex::new(Vec::new()));
+        let progress: ProgressCallback = Box::new({
+            let reports = Arc::clone(&reports);
+            move |sent, total| reports.lock().unwrap().push((sent, total))
+        });
+        let body = BodyStream::from_reader(
+            b"head".to_vec(),
+            Box::pin(io::Cursor::new(content.clone())),
+            len,
+            b"tail".to_vec(),
+            Some(progress),
+        )
+        .unwrap();
+        assert_eq!(body.len(), len + 8);
+
+        let expected = [b"head".as_slice(), &content, b"tail"].concat();
+        assert_eq!(collect(body).await.unwrap(), expected);
+
+        let reports = reports.lock().unwrap();
+        assert_eq!(reports.len(), 3);
+        assert_eq!(reports.last(), Some(&(len, len)));
+    }
+
+    #[tokio::test]
+    async fn body_from_reader_rejects_wrong_length() {
+        let body = |len| {
+            BodyStream::from_reader(
+                Vec::new(),
+                Box::pin(io::Cursor::new(vec![0; 10])),
+                len,
+                Vec::new(),
+                None,
+            )
+            .unwrap()
+        };
+        assert_eq!(
+            collect(body(11)).await.unwrap_err().kind(),
+            io::ErrorKind::UnexpectedEof
+        );
+        assert_eq!(
+            collect(body(9)).await.unwrap_err().kind(),
+            io::ErrorKind::InvalidData
+        );
+    }
+
+    #[tokio::test]
+    async fn streamed_upload_matches_buffered_upload() {
+        let content = b"content".to_vec();
+        let request = |upload: Upload| {
+            let request = Request::new(Method::POST, "upload".to_string());
+            upload.into_multipart(request, "b", b"head".to_vec(), b"tail".to_vec(), None)
+        };
+        let Some(Body::Bytes(expected)) =
+            request(Upload::Bytes(content.clone())).await.unwrap().body
+        else {
+            panic!("Expected a buffered body");
+        };
+        let Some(Body::Stream(actual)) = request(Upload::reader(io::Cursor::new(content), 7))
+            .await
+            .unwrap()
+            .body
+        else {
+            panic!("Expected a streamed body");
+        };
+        assert_eq!(actual.len(), u64::try_from(expected.len()).unwrap());
+        assert_eq!(collect(actual).await.unwrap(), expected);
+    }
+}
@@ -0,0 +1,393 @@
+//! A [`HttpClient`] wrapper that times out and retries requests.
+use std::{
+    fmt::{Debug, Formatter},
+    sync::Arc,
+    time::Duration,
+};
+
+use anyhow::anyhow;
+use log::debug;
+use reqwest::{Method, StatusCode};
+use serde::Deserialize;
+
+use super::{HttpClient, Request, Response};
+
+type ResponsePredicate = Arc<dyn Fn(&Response) -> bool + Send + Sync>;
+
+/// When and how often to retry a request.
+///
+/// Requests are retried when:
+/// - the connection could not be established, since then the request never reached the device,
+/// - the response has a status like `503 Service Unavailable` or matches [`Self::retry_if`],
+///   since then the device declined to process the request, or
+/// - any other transport error occurs, including timeouts, but only if the method is idempotent
+///   or [`Self::retry_non_idempotent`] is set.
+///
+/// Requests with streamed bodies are never retried since the body cannot be sent again.
+#[derive(Clone)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    timeout: Option<Duration>,
+    retry_statuses: Vec<StatusCode>,
+    retry_non_idempotent: bool,
+    retry_if: Option<ResponsePredicate>,
+}
+
+impl Debug for RetryPolicy {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RetryPolicy")
+            .field("max_attempts", &self.max_attempts)
+            .field("initial_backoff", &self.initial_backoff)
+            .field("max_backoff", &self.max_backoff)
+            .field("timeout", &self.timeout)
+            .field("retry_statuses", &self.retry_statuses)
+            .field("retry_non_idempotent", &self.retry_non_idempotent)
+            .field("retry_if", &self.retry_if.is_some())
+            .finish()
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(8),
+            timeout: None,
+            retry_statuses: vec![
+                StatusCode::TOO_MANY_REQUESTS,
+                StatusCode::SERVICE_UNAVAILABLE,
+            ],
+            retry_non_idempotent: false,
+            retry_if: None,
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A policy that sends every request exactly once, e.g. to only apply a timeout.
+    pub fn never() -> Self {
+        Self::default().max_attempts(1)
+    }
+
+    /// The number of times to send a request, including the first; at least 1.
+    pub fn max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// Wait `initial` before the first retry, doubling the wait before every subsequent retry up
+    /// to `max`.
+    pub fn backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max;
+        self
+    }
+
+    /// Give up on an attempt that has not completed within `timeout`.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Also retry responses with this status.
+    pub fn retry_status(mut self, status: StatusCode) -> Self {
+        self.retry_statuses.push(status);
+        self
+    }
+
+    /// Retry also requests that may have side effects if sent more than once, such as `POST`.
+    ///
+    /// Many VAPIX APIs use `POST` also for requests without side effects, so this is often safe.
+    pub fn retry_non_idempotent(mut self, retry: bool) -> Self {
+        self.retry_non_idempotent = retry;
+        self
+    }
+
+    /// Also retry responses for which `predicate` returns `true`.
+    ///
+    /// This is useful for service errors that are reported in the body, such as
+    /// [`firmware_management_1::ErrorKind::SystemBusy`](crate::apis::firmware_management_1::ErrorKind::SystemBusy),
+    /// which [`is_json_rpc_busy`] recognizes.
+    pub fn retry_if(
+        mut self,
+        predicate: impl Fn(&Response) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.retry_if = Some(Arc::new(predicate));
+        self
+    }
+
+    fn backoff_before(&self, retry: u32) -> Duration {
+        self.initial_backoff
+            .saturating_mul(2u32.saturating_pow(retry.saturating_sub(1)))
+            .min(self.max_backoff)
+    }
+
+    fn should_retry_response(&self, response: &Response) -> bool {
+        self.retry_statuses.contains(&response.status)
+            || self.retry_if.as_ref().is_some_and(|p| p(response))
+    }
+
+    fn should_retry_error(&self, method: &Method, error: &anyhow::Error) -> bool {
+        is_connect_error(error) || self.retry_non_idempotent || is_idempotent(method)
+    }
+}
+
+/// The error code that JSON-RPC style APIs use when the device is too busy to handle a request.
+const JSON_RPC_BUSY: u16 = 423;
+
+/// Returns `true` if the response is a JSON-RPC style error saying that the device is busy.
+///
+/// Intended for use with [`RetryPolicy::retry_if`].
+pub fn is_json_rpc_busy(response: &Response) -> bool {
+    #[derive(Deserialize)]
+    struct Body {
+        error: Error,
+    }
+
+    #[derive(Deserialize)]
+    struct Error {
+        code: u16,
+    }
+
+    response
+        .body
+        .as_ref()
+        .ok()
+        .and_then(|b| serde_json::from_str::<Body>(b).ok())
+        .is_some_and(|b| b.error.code == JSON_RPC_BUSY)
+}
+
+fn is_idempotent(method: &Method) -> bool {
+    [
+        Method::GET,
+        Method::HEAD,
+        Method::PUT,
+        Method::DELETE,
+        Method::OPTIONS,
+        Method::TRACE,
+    ]
+    .contains(method)
+}
+
+fn is_connect_error(error: &anyhow::Error) -> bool {
+    error.chain().any(|e| {
+        e.downcast_ref::<reqwest::Error>()
+            .is_some_and(reqwest::Error::is_connect)
+    })
+}
+
+/// Wraps another client, applying a [`RetryPolicy`] to every request.
+#[derive(Clone, Debug)]
+pub struct RetryClient<C> {
+    inner: C,
+    policy: RetryPolicy,
+}
+
+impl<C> RetryClient<C> {
+    pub fn new(inner: C, policy: RetryPolicy) -> Self {
+        Self { inner, policy }
+    }
+
+    pub fn inner(&self) -> &C {
+        &self.inner
+    }
+}
+
+impl<C: HttpClient + Sync> RetryClient<C> {
+    async fn execute_once(&self, request: Request) -> anyhow::Result<Response> {
+        match self.policy.timeout {
+            None => self.inner.execute(request).await,
+            Some(timeout) => tokio::time::timeout(timeout, self.inner.execute(request))
+                .await
+                .map_err(|_| anyhow!("Request timed out after {timeout:?}"))?,
+        }
+    }
+}
+
+impl<C: HttpClient + Sync> HttpClient for RetryClient<C> {
+    async fn execute(&self, request: Request) -> Result<Response, anyhow::Error> {
+        let mut request = request;
+        let mut attempt = 1;
+        loop {
+            let method = request.method.clone();
+            let path = request.path.clone();
+            let next = match attempt < self.policy.max_attempts {
+                true => request.try_clone(),
+                false => None,
+            };
+            let result = self.execute_once(request).await;
+            let Some(next) = next else {
+                return result;
+            };
+            match &result {
+                Ok(response) if self.policy.should_retry_response(response) => {
+                    debug!(
+                        "Attempt {attempt} to {method} {path} got {}, retrying",
+                        response.status
+                    );
+                }
+                Err(e) if self.policy.should_retry_error(&method, e) => {
+                    debug!("Attempt {attempt} to {method} {path} failed, retrying: {e:?}");
+                }
+                _ => return result,
+            }
+            tokio::time::sleep(self.policy.backoff_before(attempt)).await;
+            request = next;
+            attempt += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::VecDeque, sync::Mutex};
+
+    use super::*;
+
+    struct FakeClient {
+        results: Mutex<VecDeque<anyhow::Result<Response>>>,
+        attempts: Mutex<u32>,
+    }
+
+    impl FakeClient {
+        fn new(results: Vec<anyhow::Result<Response>>) -> Self {
+            Self {
+                results: Mutex::new(results.into()),
+                attempts: Mutex::new(0),
+            }
+        }
+
+        fn attempts(&self) -> u32 {
+            *self.attempts.lock().unwrap()
+        }
+    }
+
+    impl HttpClient for FakeClient {
+        async fn execute(&self, _request: Request) -> Result<Response, anyhow::Error> {
+            *self.attempts.lock().unwrap() += 1;
+            self.results.lock().unwrap().pop_front().unwrap()
+        }
+    }
+
+    fn response(status: StatusCode) -> anyhow::Result<Response> {
+        Ok(Response {
+            status,
+            body: Ok(String::new()),
+        })
+    }
+
+    fn policy() -> RetryPolicy {
+        RetryPolicy::new().backoff(Duration::ZERO, Duration::ZERO)
+    }
+
+    fn get() -> Request {
+        Request::new(Method::GET, "axis-cgi/param.cgi".to_string())
+    }
+
+    fn post() -> Request {
+        Request::new(Method::POST, "axis-cgi/basicdeviceinfo.cgi".to_string()).json("{}".into())
+    }
+
+    #[tokio::test]
+    async fn retries_unavailable_until_ok() {
+        let client = RetryClient::new(
+            FakeClient::new(vec![
+                response(StatusCode::SERVICE_UNAVAILABLE),
+                Err(anyhow!("connection reset")),
+                response(StatusCode::OK),
+            ]),
+            policy(),
+        );
+        let response = client.execute(get()).await.unwrap();
+        assert_eq!(response.status, StatusCode::OK);
+        assert_eq!(client.inner().attempts(), 3);
+    }
+
+    #[tokio::test]
+    async fn returns_last_response_when_attempts_are_exhausted() {
+        let client = RetryClient::new(
+            FakeClient::new(vec![
+                response(StatusCode::SERVICE_UNAVAILABLE),
+                response(StatusCode::SERVICE_UNAVAILABLE),
+            ]),
+            policy().max_attempts(2),
+        );
+        let response = client.execute(post()).await.unwrap();
+        assert_eq!(response.status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(client.inner().attempts(), 2);
+    }
+
+    #[tokio::test]
+    async fn does_not_retry_non_idempotent_transport_errors() {
+        let client = RetryClient::new(
+            FakeClient::new(vec![Err(anyhow!("connection reset"))]),
+            policy(),
+        );
+        client.execute(post()).await.unwrap_err();
+        assert_eq!(client.inner().attempts(), 1);
+
+        let client = RetryClient::new(
+            FakeClient::new(vec![
+                Err(anyhow!("connection reset")),
+                response(StatusCode::OK),
+            ]),
+            policy().retry_non_idempotent(true),
+        );
+        client.execute(post()).await.unwrap();
+        assert_eq!(client.inner().attempts(), 2);
+    }
+
+    #[tokio::test]
+    async fn retries_responses_matching_predicate() {
+        let client = RetryClient::new(
+            FakeClient::new(vec![
+                Ok(Response {
+                    status: StatusCode::OK,
+                    body: Ok(r#"{"error":{"code":423}}"#.to_string()),
+                }),
+                response(StatusCode::OK),
+            ]),
+            policy().retry_if(|r| r.body.as_ref().is_ok_and(|b| b.contains("423"))),
+        );
+        client.execute(post()).await.unwrap();
+        assert_eq!(client.inner().attempts(), 2);
+    }
+
+    #[tokio::test]
+    async fn retries_json_rpc_busy() {
+        let busy = Response {
+            status: StatusCode::OK,
+            body: Ok(r#"{"apiVersion":"1.0","error":{"code":423,"message":"Busy"}}"#.to_string()),
+        };
+        assert!(is_json_rpc_busy(&busy));
+        let other = Response {
+            status: StatusCode::OK,
+            body: Ok(r#"{"apiVersion":"1.0","error":{"code":400,"message":"423"}}"#.to_string()),
+        };
+        assert!(!is_json_rpc_busy(&other));
+
+        let client = RetryClient::new(
+            FakeClient::new(vec![Ok(busy), response(StatusCode::OK)]),
+            policy().retry_if(is_json_rpc_busy),
+        );
+        client.execute(post()).await.unwrap();
+        assert_eq!(client.inner().attempts(), 2);
+    }
+
+    #[test]
+    fn backoff_is_exponential_and_capped() {
+        let policy = RetryPolicy::new().backoff(Duration::from_secs(1), Duration::from_secs(5));
+        assert_eq!(policy.backoff_before(1), Duration::from_secs(1));
+        assert_eq!(policy.backoff_before(2), Duration::from_secs(2));
+        assert_eq!(policy.backoff_before(3), Duration::from_secs(4));
+        assert_eq!(policy.backoff_before(4), Duration::from_secs(5));
+        assert_eq!(policy.backoff_before(40), Duration::from_secs(5));
+    }
+}
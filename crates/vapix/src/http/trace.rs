@@ -0,0 +1,303 @@
+//! A [`HttpClient`] wrapper that records every exchange with secrets redacted.
+//!
+//! Records are emitted using the `log` crate, so they end up wherever other logs do, e.g. in the
+//! files written by `rs4a_bin_utils::logger`, and can optionally be passed to a callback.
+use std::{
+    fmt::{Debug, Display, Formatter},
+    sync::{Arc, LazyLock},
+    time::{Duration, Instant},
+};
+
+use log::{log, Level};
+use regex::Regex;
+use reqwest::{Method, StatusCode};
+
+use super::{Body, HttpClient, Request, Response};
+
+const REDACTED: &str = "***";
+
+static QUERY_SECRET: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)([?&](?:pwd|password|passwd|pass|secret|token)=)[^&\s]*").unwrap()
+});
+
+static JSON_SECRET: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r#"(?i)("[^"]*(?:password|passwd|pwd|secret|token|privatekey)[^"]*"\s*:\s*)"(?:[^"\\]|\\.)*""#,
+    )
+    .unwrap()
+});
+
+static XML_SECRET: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)<((?:\w+:)?\w*(?:password|privatekey|secret|token)\w*)(\s[^>]*[^/>])?>[^<]*<")
+        .unwrap()
+});
+
+static HEADER_SECRET: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?im)^((?:proxy-)?authorization|cookie|set-cookie):[^\r\n]*").unwrap()
+});
+
+/// Replace credentials and other secrets in `text` with `***`.
+///
+/// Recognizes query parameters such as `pwd=`, JSON fields and XML elements with names like
+/// `password` or `secretAccessKey`, and `Authorization` or `Cookie` header lines.
+pub fn redact(text: &str) -> String {
+    let text = QUERY_SECRET.replace_all(text, format!("${{1}}{REDACTED}"));
+    let text = JSON_SECRET.replace_all(&text, format!("${{1}}\"{REDACTED}\""));
+    let text = XML_SECRET.replace_all(&text, format!("<${{1}}${{2}}>{REDACTED}<"));
+    let text = HEADER_SECRET.replace_all(&text, format!("${{1}}: {REDACTED}"));
+    text.into_owned()
+}
+
+/// A record of one request and its response.
+#[derive(Clone, Debug)]
+pub struct Exchange {
+    pub method: Method,
+    /// The path and query, redacted.
+    pub path: String,
+    /// The status, unless the request failed before a response was received.
+    pub status: Option<StatusCode>,
+    pub latency: Duration,
+    pub request_body_len: u64,
+    pub response_body_len: Option<usize>,
+    /// The request body, redacted, if bodies are recorded and it is text.
+    pub request_body: Option<String>,
+    /// The response body, redacted, if bodies are recorded.
+    pub response_body: Option<String>,
+    /// The transport error, redacted, if any.
+    pub error: Option<String>,
+}
+
+impl Display for Exchange {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let Self {
+            method,
+            path,
+            status,
+            latency,
+            request_body_len,
+            response_body_len,
+            ..
+        } = self;
+        write!(f, "{method} {path} -> ")?;
+        match (status, &self.error) {
+            (Some(status), _) => write!(f, "{status}")?,
+            (None, Some(error)) => write!(f, "error: {error}")?,
+            (None, None) => write!(f, "no response")?,
+        }
+        write!(f, " in {latency:?} (sent {request_body_len} B")?;
+        if let Some(len) = response_body_len {
+            write!(f, ", received {len} B")?;
+        }
+        write!(f, ")")
+    }
+}
+
+type ExchangeCallback = Arc<dyn Fn(&Exchange) + Send + Sync>;
+
+/// Wraps another client, recording every exchange.
+#[derive(Clone)]
+pub struct TracingClient<C> {
+    inner: C,
+    level: Level,
+    bodies: bool,
+    on_exchange: Option<ExchangeCallback>,
+}
+
+impl<C: Debug> Debug for TracingClient<C> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TracingClient")
+            .field("inner", &self.inner)
+            .field("level", &self.level)
+            .field("bodies", &self.bodies)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<C> TracingClient<C> {
+    /// Log a summary of every exchange at debug level.
+    pub fn new(inner: C) -> Self {
+        Self {
+            inner,
+            level: Level::Debug,
+            bodies: false,
+            on_exchange: None,
+        }
+    }
+
+    pub fn level(mut self, level: Level) -> Self {
+        self.level = level;
+        self
+    }
+
+    /// Also record text bodies; multipart bodies, such as firmware uploads, are never recorded.
+    pub fn bodies(mut self, enabled: bool) -> Self {
+        self.bodies = enabled;
+        self
+    }
+
+    /// Pass every record to `callback` in addition to logging it.
+    pub fn on_exchange(mut self, callback: impl Fn(&Exchange) + Send + Sync + 'static) -> Self {
+        self.on_exchange = Some(Arc::new(callback));
+        self
+    }
+
+    pub fn inner(&self) -> &C {
+        &self.inner
+    }
+}
+
+fn request_body_text(request: &Request) -> Option<String> {
+    if request
+        .content_type
+        .as_deref()
+        .is_some_and(|c| c.starts_with("multipart/"))
+    {
+        return None;
+    }
+    let bytes = request.body.as_ref()?.as_bytes()?;
+    Some(redact(&String::from_utf8_lossy(bytes)))
+}
+
+impl<C: HttpClient + Sync> HttpClient for TracingClient<C> {
+    async fn execute(&self, request: Request) -> Result<Response, anyhow::Error> {
+        let method = request.method.clone();
+        let path = redact(&request.path);
+        let request_body_len = match &request.body {
+            None => 0,
+            Some(Body::Bytes(bytes)) => u64::try_from(bytes.len()).unwrap_or(u64::MAX),
+            Some(Body::Stream(stream)) => stream.len(),
+        };
+        let request_body = match self.bodies {
+            true => request_body_text(&request),
+            false => None,
+        };
+
+        let start = Instant::now();
+        let result = self.inner.execute(request).await;
+        let latency = start.elapsed();
+
+        let response_text = result.as_ref().ok().and_then(|r| r.body.as_ref().ok());
+        let exchange = Exchange {
+            method,
+            path,
+            status: result.as_ref().ok().map(|r| r.status),
+            latency,
+            request_body_len,
+            response_body_len: response_text.map(String::len),
+            request_body,
+            response_body: match self.bodies {
+                true => response_text.map(|t| redact(t)),
+                false => None,
+            },
+            error: result.as_ref().err().map(|e| redact(&format!("{e:#}"))),
+        };
+        log!(self.level, "{exchange}");
+        if let Some(body) = &exchange.request_body {
+            log!(self.level, "Request body: {body}");
+        }
+        if let Some(body) = &exchange.response_body {
+            log!(self.level, "Response body: {body}");
+        }
+        if let Some(callback) = &self.on_exchange {
+            callback(&exchange);
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+
+    #[test]
+    fn redacts_query_parameters() {
+        assert_eq!(
+            redact("axis-cgi/pwdgrp.cgi?action=add&user=bob&pwd=hunter2&grp=users"),
+            "axis-cgi/pwdgrp.cgi?action=add&user=bob&pwd=***&grp=users"
+        );
+        assert_eq!(
+            redact("axis-cgi/param.cgi?Password=x"),
+            "axis-cgi/param.cgi?Password=***"
+        );
+        assert_eq!(
+            redact("axis-cgi/param.cgi?group=Passwd"),
+            "axis-cgi/param.cgi?group=Passwd"
+        );
+        assert_eq!(
+            redact("axis-cgi/pwdgrp.cgi?action=update&user=x&pwd=hunter2"),
+            "axis-cgi/pwdgrp.cgi?action=update&user=x&pwd=***"
+        );
+    }
+
+    #[test]
+    fn redacts_json_fields() {
+        assert_eq!(
+            redact(r#"{"data": {"username": "bob", "password": "a\"b", "secretAccessKey": "k"}}"#),
+            r#"{"data": {"username": "bob", "password": "***", "secretAccessKey": "***"}}"#
+        );
+    }
+
+    #[test]
+    fn redacts_xml_elements() {
+        assert_eq!(
+            redact("<Id>lab</Id><PrivateKey>MHcC</PrivateKey><acert:Password>x</acert:Password>"),
+            "<Id>lab</Id><PrivateKey>***</PrivateKey><acert:Password>***</acert:Password>"
+        );
+        assert_eq!(
+            redact(
+                r#"<tt:Password Type="PasswordText">x</tt:Password><Password xsi:type="xsd:string">y</Password>"#
+            ),
+            r#"<tt:Password Type="PasswordText">***</tt:Password><Password xsi:type="xsd:string">***</Password>"#
+        );
+        assert_eq!(
+            redact(r#"<Password xsi:nil="true"/><Id>lab</Id>"#),
+            r#"<Password xsi:nil="true"/><Id>lab</Id>"#
+        );
+    }
+
+    #[test]
+    fn redacts_headers() {
+        assert_eq!(
+            redact("Content-Type: text/plain\r\nAuthorization: Digest username=\"root\"\r\n"),
+            "Content-Type: text/plain\r\nAuthorization: ***\r\n"
+        );
+    }
+
+    struct FakeClient;
+
+    impl HttpClient for FakeClient {
+        async fn execute(&self, _request: Request) -> Result<Response, anyhow::Error> {
+            Ok(Response {
+                status: StatusCode::OK,
+                body: Ok(r#"{"data": {"password": "hunter2"}}"#.to_string()),
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn records_redacted_exchange() {
+        let exchanges = Arc::new(Mutex::new(Vec::new()));
+        let client = TracingClient::new(FakeClient).bodies(true).on_exchange({
+            let exchanges = Arc::clone(&exchanges);
+            move |e| exchanges.lock().unwrap().push(e.clone())
+        });
+        let request = Request::new(Method::POST, "config/rest/ssh/v2/users?pwd=x".to_string())
+            .json(r#"{"data": {"password": "hunter2"}}"#.to_string());
+        client.execute(request).await.unwrap();
+
+        let exchanges = exchanges.lock().unwrap();
+        let [exchange] = exchanges.as_slice() else {
+            panic!("Expected one exchange but got {exchanges:#?}");
+        };
+        assert_eq!(exchange.path, "config/rest/ssh/v2/users?pwd=***");
+        assert_eq!(exchange.status, Some(StatusCode::OK));
+        assert_eq!(exchange.request_body_len, 33);
+        assert_eq!(exchange.response_body_len, Some(33));
+        assert!(!format!("{exchange:?}").contains("hunter2"));
+        assert!(exchange
+            .to_string()
+            .starts_with("POST config/rest/ssh/v2/users?pwd=*** -> 200 OK"));
+    }
+}
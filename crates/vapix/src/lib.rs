@@ -3,5 +3,8 @@ mod client;
 pub mod http;
 pub mod protocol_helpers;
 pub mod requests;
+pub mod tls;
 
-pub use client::{Client, ClientBuilder, RequestBuilder, Scheme};
+pub use client::{
+    Client, ClientBuilder, RequestBuilder, Scheme, SchemeError, SchemePolicy, SchemeReport,
+};
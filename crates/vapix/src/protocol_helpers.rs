@@ -19,3 +19,4 @@ pub mod rest;
 pub mod rest_http;
 pub mod soap;
 pub mod soap_http;
+pub mod text_http;
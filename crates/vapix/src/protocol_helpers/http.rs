@@ -1,5 +1,12 @@
 //! Utilities for working with any HTTP-based APIs.
 
+use url::form_urlencoded::byte_serialize;
+
+/// Percent-encode `value` for use in a query string.
+pub fn encode_query_value(value: &str) -> String {
+    byte_serialize(value.as_bytes()).collect()
+}
+
 /// Error type for HTTP-based APIs
 #[derive(Debug, thiserror::Error)]
 pub enum Error<E> {
@@ -31,6 +31,26 @@ impl<'a> Response<'a> {
     }
 }
 
+/// The body of a request to a JSON-RPC-style API.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RequestBody<P> {
+    api_version: &'static str,
+    method: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    params: Option<P>,
+}
+
+impl<P> RequestBody<P> {
+    pub fn new(api_version: &'static str, method: &'static str, params: Option<P>) -> Self {
+        Self {
+            api_version,
+            method,
+            params,
+        }
+    }
+}
+
 /// Error returned by all JSON-RPC-style APIs
 #[derive(Debug, Deserialize, Serialize)]
 pub struct Error {
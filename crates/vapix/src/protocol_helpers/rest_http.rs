@@ -6,7 +6,7 @@ use reqwest::StatusCode;
 use serde::{Deserialize, Serialize};
 
 use super::{http::Error, rest, rest::parse_data_lossless};
-use crate::http::{HttpClient, Request};
+use crate::http::{redact, HttpClient, Request};
 
 // The device configuration API reports status codes both in the HTTP header and in the body.
 // TODO: Consider if there is any value in this.
@@ -22,7 +22,7 @@ where
         .with_context(|| format!("Could not fetch text, status was {http_status}"))
         .map_err(Error::Transport)?;
     if cfg!(debug_assertions) {
-        trace!("Received {http_status}: {text}");
+        trace!("Received {http_status}: {}", redact(&text));
     }
     Error::flat_result(parse_data_lossless(&text))
 }
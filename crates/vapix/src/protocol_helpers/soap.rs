@@ -26,8 +26,23 @@ where
 }
 
 pub fn envelope(namespace: &str, method: &str, params: Option<&str>) -> String {
+    envelope_with_header(None, namespace, method, params)
+}
+
+/// Like [`envelope`] but with the given elements in the `soap:Header`.
+pub fn envelope_with_header(
+    header: Option<&str>,
+    namespace: &str,
+    method: &str,
+    params: Option<&str>,
+) -> String {
     let mut s = String::new();
     s.push_str(r#"<soap:Envelope xmlns:soap="http://www.w3.org/2003/05/soap-envelope">"#);
+    if let Some(header) = header {
+        s.push_str(r#"<soap:Header>"#);
+        s.push_str(header);
+        s.push_str(r#"</soap:Header>"#);
+    }
     s.push_str(r#"<soap:Body>"#);
     s.push('<');
     s.push_str(method);
@@ -0,0 +1,33 @@
+//! Utilities for working with APIs that respond with plain text over HTTP.
+
+use std::convert::Infallible;
+
+use anyhow::Context;
+use reqwest::StatusCode;
+
+use super::http::Error;
+use crate::http::{HttpClient, Request};
+
+/// Returns the text of the response if the status indicates success.
+pub fn from_response(
+    http_status: StatusCode,
+    text: reqwest::Result<String>,
+) -> Result<String, Error<Infallible>> {
+    let text = text
+        .with_context(|| format!("Could not fetch text, status was {http_status}"))
+        .map_err(Error::Transport)?;
+    if !http_status.is_success() {
+        return Err(Error::Decode(anyhow::anyhow!(
+            "Unexpected status {http_status}; text: {text}"
+        )));
+    }
+    Ok(text)
+}
+
+pub async fn send_request(
+    client: &(impl HttpClient + Sync),
+    request: Request,
+) -> Result<String, Error<Infallible>> {
+    let response = client.execute(request).await.map_err(Error::Transport)?;
+    from_response(response.status, response.body)
+}
@@ -11,14 +11,23 @@ pub mod discover {
     pub use crate::apis::discover::DiscoverRequest;
 }
 
+pub mod applications {
+    pub use crate::apis::applications::{
+        ControlRequest, ListRequest, UploadLicenseRequest, UploadRequest,
+    };
+}
+
 pub mod applications_config {
     pub use crate::apis::applications_config::ApplicationConfigRequest;
 }
 
 pub mod action_1 {
     pub use crate::apis::action1::{
-        AddActionConfigurationRequest, AddActionRuleRequest, GetActionConfigurationsRequest,
-        GetActionRulesRequest,
+        AddActionConfigurationRequest, AddActionRuleRequest, AddRecipientConfigurationRequest,
+        GetActionConfigurationsRequest, GetActionRulesRequest, GetActionTemplatesRequest,
+        GetRecipientConfigurationsRequest, GetRecipientTemplatesRequest,
+        RemoveActionConfigurationRequest, RemoveActionRuleRequest,
+        RemoveRecipientConfigurationRequest, ReplaceActionRuleRequest,
     };
 }
 pub mod basic_device_info_1 {
@@ -27,23 +36,41 @@ pub mod basic_device_info_1 {
     };
 }
 
+pub mod cert {
+    pub use crate::apis::cert::{
+        AddCACertificateRequest, CreateSelfSignedCertificateRequest, DeleteCertificatesRequest,
+        GetCACertificatesRequest, GetCertificatesRequest, LoadCertificateWithPrivateKeyRequest,
+        SetHttpsCertificateRequest,
+    };
+}
+
 pub mod event_1 {
-    pub use crate::apis::event1::GetEventInstancesRequest;
+    pub use crate::apis::event1::{
+        CreatePullPointSubscriptionRequest, GetEventInstancesRequest, PullMessagesRequest,
+        RenewRequest, UnsubscribeRequest,
+    };
+}
+
+pub mod event_stream_1 {
+    pub use crate::apis::event_stream_1::SubscribeRequest;
 }
 
 pub mod jpg_3 {
-    pub use crate::apis::jpg_3::GetImageRequest;
+    pub use crate::apis::jpg_3::{GetImageRequest, GetMjpegStreamRequest};
 }
 
 pub mod remote_object_storage_1_beta {
     pub use crate::apis::remote_object_storage_1_beta::{
-        CreateDestinationRequest, DeleteDestinationRequest, ListDestinationsRequest,
-        UpdateDestinationRequest,
+        CreateDestinationRequest, DeleteDestinationRequest, GetDestinationRequest,
+        ListDestinationsRequest, UpdateDestinationRequest,
     };
 }
 
 pub mod recording_group_1 {
-    pub use crate::apis::recording_group_1::CreateRecordingGroupsRequest;
+    pub use crate::apis::recording_group_1::{
+        CreateRecordingGroupsRequest, DeleteRecordingGroupRequest, GetRecordingGroupRequest,
+        ListRecordingGroupsRequest, UpdateRecordingGroupRequest,
+    };
 }
 
 pub mod siren_and_light_2_alpha {
@@ -56,24 +83,54 @@ pub mod ssh_1 {
     pub use crate::apis::ssh_1::{AddUserRequest, DeleteUserRequest, SetUserRequest};
 }
 
+pub mod ssh_2 {
+    pub use crate::apis::ssh_2::{
+        AddUserRequest, DeleteUserRequest, GetServerConfigRequest, ListUsersRequest,
+        SetServerConfigRequest, SetUserRequest,
+    };
+}
+
+pub mod server_report {
+    pub use crate::apis::server_report::{DownloadServerReportRequest, GetServerReportRequest};
+}
+
+pub mod system_log {
+    pub use crate::apis::system_log::GetSystemLogRequest;
+}
+
 pub mod system_ready_1 {
     pub use crate::apis::system_ready_1::SystemReadyRequest;
 }
 
+pub mod time_1 {
+    pub use crate::apis::time_1::{GetDateTimeInfoRequest, SetDateTimeRequest, SetTimeZoneRequest};
+}
+
 pub mod firmware_management_1 {
-    pub use crate::apis::firmware_management_1::{FactoryDefaultRequest, UpgradeRequest};
+    pub use crate::apis::firmware_management_1::{
+        CommitRequest, FactoryDefaultRequest, PurgeRequest, RebootRequest, RollbackRequest,
+        StatusRequest, UpgradeRequest,
+    };
 }
 
 pub mod network_settings_1 {
     pub use crate::apis::network_settings_1::{
-        GetNetworkInfoRequest, SetGlobalProxyConfigurationRequest,
+        GetNetworkInfoRequest, Set8021XConfigurationRequest, SetGlobalProxyConfigurationRequest,
+        SetHostnameConfigurationRequest, SetIpv4AddressConfigurationRequest,
+        SetIpv6AddressConfigurationRequest, SetResolverConfigurationRequest,
     };
 }
 
+pub mod ntp_1 {
+    pub use crate::apis::ntp_1::{GetNtpInfoRequest, SetNtpClientConfigurationRequest};
+}
+
 pub mod parameter_management {
     pub use crate::apis::parameter_management::{ListRequest, UpdateRequest};
 }
 
 pub mod pwdgrp {
-    pub use crate::apis::pwdgrp::{AddUserRequest, RemoveUserRequest};
+    pub use crate::apis::pwdgrp::{
+        AddUserRequest, GetUsersRequest, RemoveUserRequest, UpdateUserRequest,
+    };
 }
@@ -0,0 +1,243 @@
+//! Utilities for trusting devices that do not have a certificate signed by a public CA.
+//!
+//! Pinning certificates requires the `rustls-tls` feature, which is enabled by default.
+#[cfg(feature = "rustls-tls")]
+use std::sync::{Arc, Mutex};
+use std::{
+    fmt::{Display, Formatter},
+    str::FromStr,
+};
+
+use anyhow::Context;
+#[cfg(feature = "rustls-tls")]
+use log::debug;
+#[cfg(feature = "rustls-tls")]
+use rustls::{
+    client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier},
+    crypto::{ring::default_provider, CryptoProvider},
+    pki_types::{CertificateDer, ServerName, UnixTime},
+    CertificateError, ClientConfig, DigitallySignedStruct, SignatureScheme,
+};
+#[cfg(feature = "rustls-tls")]
+use url::Host;
+
+/// The SHA-256 digest of a DER encoded certificate.
+///
+/// Formatted as colon separated hex pairs, like `openssl x509 -fingerprint -sha256` does.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct CertificateFingerprint([u8; 32]);
+
+impl CertificateFingerprint {
+    #[cfg(feature = "rustls-tls")]
+    pub fn of_der(der: &[u8]) -> Self {
+        let digest = ring::digest::digest(&ring::digest::SHA256, der);
+        let mut bytes = [0; 32];
+        bytes.copy_from_slice(digest.as_ref());
+        Self(bytes)
+    }
+}
+
+impl Display for CertificateFingerprint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (i, b) in self.0.iter().enumerate() {
+            if i != 0 {
+                write!(f, ":")?;
+            }
+            write!(f, "{b:02X}")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for CertificateFingerprint {
+    type Err = anyhow::Error;
+
+    /// Parse a fingerprint with or without colons and in either case.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let hex: String = s.chars().filter(|c| *c != ':').collect();
+        anyhow::ensure!(
+            hex.len() == 64 && hex.is_ascii(),
+            "Expected 32 hex encoded bytes but got {s:?}"
+        );
+        let mut bytes = [0; 32];
+        for (b, i) in bytes.iter_mut().zip((0..hex.len()).step_by(2)) {
+            let pair = hex.get(i..i + 2).context("Expected ASCII")?;
+            *b = u8::from_str_radix(pair, 16)
+                .with_context(|| format!("Expected hex but got {pair:?} in {s:?}"))?;
+        }
+        Ok(Self(bytes))
+    }
+}
+
+/// Accepts only the leaf certificate with a given fingerprint, or any leaf certificate when
+/// fetching the fingerprint using [`fetch_certificate_fingerprint`].
+///
+/// Neither the chain nor the server name is verified; the fingerprint is what establishes trust.
+/// Handshake signatures are still verified so that the peer must hold the private key.
+#[cfg(feature = "rustls-tls")]
+#[derive(Debug)]
+struct FingerprintVerifier {
+    expected: Option<CertificateFingerprint>,
+    seen: Arc<Mutex<Option<CertificateFingerprint>>>,
+    provider: Arc<CryptoProvider>,
+}
+
+#[cfg(feature = "rustls-tls")]
+impl ServerCertVerifier for FingerprintVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        let actual = CertificateFingerprint::of_der(end_entity.as_ref());
+        *self.seen.lock().unwrap_or_else(|e| e.into_inner()) = Some(actual);
+        match self.expected {
+            Some(expected) if expected != actual => {
+                debug!("Expected certificate fingerprint {expected} but got {actual}");
+                Err(rustls::Error::InvalidCertificate(
+                    CertificateError::ApplicationVerificationFailure,
+                ))
+            }
+            _ => Ok(ServerCertVerified::assertion()),
+        }
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls12_signature(
+            message,
+            cert,
+            dss,
+            &self.provider.signature_verification_algorithms,
+        )
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls13_signature(
+            message,
+            cert,
+            dss,
+            &self.provider.signature_verification_algorithms,
+        )
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.provider
+            .signature_verification_algorithms
+            .supported_schemes()
+    }
+}
+
+#[cfg(feature = "rustls-tls")]
+fn client_config(verifier: FingerprintVerifier) -> anyhow::Result<ClientConfig> {
+    let provider = Arc::clone(&verifier.provider);
+    let mut config = ClientConfig::builder_with_provider(provider)
+        .with_safe_default_protocol_versions()?
+        .dangerous()
+        .with_custom_certificate_verifier(Arc::new(verifier))
+        .with_no_client_auth();
+    // Matches what reqwest does for its own configurations when HTTP/2 is enabled.
+    config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+    Ok(config)
+}
+
+/// A TLS configuration that trusts only the leaf certificate with the given fingerprint.
+#[cfg(feature = "rustls-tls")]
+fn pinned_config(fingerprint: CertificateFingerprint) -> anyhow::Result<ClientConfig> {
+    client_config(FingerprintVerifier {
+        expected: Some(fingerprint),
+        seen: Arc::new(Mutex::new(None)),
+        provider: Arc::new(default_provider()),
+    })
+}
+
+/// Configure `builder` to trust only the leaf certificate with the given fingerprint.
+#[cfg(feature = "rustls-tls")]
+pub(crate) fn pin(
+    builder: reqwest::ClientBuilder,
+    fingerprint: CertificateFingerprint,
+) -> anyhow::Result<reqwest::ClientBuilder> {
+    Ok(builder.use_preconfigured_tls(pinned_config(fingerprint)?))
+}
+
+#[cfg(not(feature = "rustls-tls"))]
+pub(crate) fn pin(
+    _builder: reqwest::ClientBuilder,
+    _fingerprint: CertificateFingerprint,
+) -> anyhow::Result<reqwest::ClientBuilder> {
+    anyhow::bail!("Pinning certificates requires the rustls-tls feature")
+}
+
+/// Connect to a device and return the fingerprint of the certificate that it presents.
+///
+/// The certificate is not verified in any way, so this should be used only to pin a certificate
+/// on first use over a network that is trusted at that time.
+#[cfg(feature = "rustls-tls")]
+pub async fn fetch_certificate_fingerprint(
+    host: &Host,
+    port: Option<u16>,
+) -> anyhow::Result<CertificateFingerprint> {
+    let seen = Arc::new(Mutex::new(None));
+    let config = client_config(FingerprintVerifier {
+        expected: None,
+        seen: Arc::clone(&seen),
+        provider: Arc::new(default_provider()),
+    })?;
+    let client = reqwest::Client::builder()
+        .use_preconfigured_tls(config)
+        .build()?;
+    let url = match port {
+        None => format!("https://{host}/"),
+        Some(port) => format!("https://{host}:{port}/"),
+    };
+    // The response is irrelevant; only the handshake is of interest.
+    if let Err(e) = client.head(&url).send().await {
+        debug!("Request to {url} failed: {e:?}");
+    }
+    let fingerprint = *seen.lock().unwrap_or_else(|e| e.into_inner());
+    fingerprint.with_context(|| format!("Could not complete a TLS handshake with {url}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fingerprint_round_trips() {
+        let fingerprint =
+            CertificateFingerprint(std::array::from_fn(|i| u8::try_from(i * 7).unwrap()));
+        let text = fingerprint.to_string();
+        assert_eq!(text.len(), 32 * 3 - 1);
+        assert_eq!(text.parse::<CertificateFingerprint>().unwrap(), fingerprint);
+        assert_eq!(
+            text.replace(':', "")
+                .to_lowercase()
+                .parse::<CertificateFingerprint>()
+                .unwrap(),
+            fingerprint
+        );
+    }
+
+    #[test]
+    fn fingerprint_rejects_invalid_text() {
+        assert!("AB:CD".parse::<CertificateFingerprint>().is_err());
+        assert!("Z".repeat(64).parse::<CertificateFingerprint>().is_err());
+        assert!("\u{e5}"
+            .repeat(32)
+            .parse::<CertificateFingerprint>()
+            .is_err());
+    }
+}
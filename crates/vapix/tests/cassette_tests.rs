@@ -1,4 +1,4 @@
-use std::{future::Future, pin::Pin};
+use std::{future::Future, pin::Pin, time::Duration};
 
 use anyhow::Context;
 use libtest_mimic::{Arguments, Trial};
@@ -7,9 +7,11 @@ use rs4a_cassette_testing::{Cassette, CassetteClient, DeviceInfo, Library};
 use rs4a_vapix::{
     apis::{
         action1::{
-            AddActionConfigurationRequest, AddActionRuleRequest, Condition,
-            GetActionConfigurationsRequest, GetActionRulesRequest,
-            RemoveActionConfigurationRequest, RemoveActionRuleRequest,
+            AddActionConfigurationRequest, AddActionRuleRequest, AddRecipientConfigurationRequest,
+            Condition, GetActionConfigurationsRequest, GetActionRulesRequest,
+            GetActionTemplatesRequest, GetRecipientConfigurationsRequest,
+            GetRecipientTemplatesRequest, RemoveActionConfigurationRequest,
+            RemoveActionRuleRequest, RemoveRecipientConfigurationRequest,
         },
         api_discovery_1::{Api, ApiListData, GetApiListRequest},
         basic_device_info_1::{
@@ -17,14 +19,27 @@ use rs4a_vapix::{
             UnrestrictedProperties,
         },
         discover::ApiState,
-        event1::GetEventInstancesRequest,
+        event1::{
+            CreatePullPointSubscriptionRequest, GetEventInstancesRequest, PropertyOperation,
+            PullMessagesRequest, RenewRequest, UnsubscribeRequest,
+        },
         firmware_management_1,
-        firmware_management_1::UpgradeRequest,
-        network_settings_1::{GetNetworkInfoRequest, SetGlobalProxyConfigurationRequest},
-        parameter_management::{ImageResolution, ListRequest, NetworkSshEnabled, UpdateRequest},
+        firmware_management_1::{PurgeRequest, StatusRequest, UpgradeRequest},
+        network_settings_1::{
+            GetNetworkInfoRequest, SetGlobalProxyConfigurationRequest,
+            SetResolverConfigurationRequest,
+        },
+        parameter_management::{
+            BrandProdNbr, ImageResolution, ListRequest, NetworkSshEnabled, UpdateRequest,
+        },
+        recording_group_1::{
+            CreateRecordingGroupsRequest, DeleteRecordingGroupRequest, Destination,
+            GetRecordingGroupRequest, ListRecordingGroupsRequest, UpdateRecordingGroupRequest,
+        },
         remote_object_storage_1_beta::{
             AzureDestination, CreateDestinationRequest, DeleteDestinationRequest, DestinationData,
-            DestinationId, ListDestinationsRequest, UpdateDestinationRequest,
+            DestinationId, GetDestinationRequest, ListDestinationsRequest, S3Destination,
+            UpdateDestinationRequest,
         },
         siren_and_light_2_alpha::{
             GetMaintenanceModeRequest, StartMaintenanceModeRequest, StopMaintenanceModeRequest,
@@ -141,11 +156,15 @@ cassette_tests! {
     action1_add_action_rule_unknown_configuration,
     action1_get_action_configurations,
     action1_get_action_rules,
+    action1_get_action_templates,
+    action1_recipient_configuration_crud,
     action1_remove_action_configuration_in_use,
     action1_remove_action_configuration_unknown,
     action1_remove_action_rule_unknown,
     api_discovery_1_get_api_list,
     api_discovery_1_get_supported_versions,
+    applications_list,
+    cert_create_self_signed_certificate,
     network_settings_1_get_network_info => [
         // MAC address
         (
@@ -196,6 +215,32 @@ cassette_tests! {
             r#""identity": "axis-0123456789ab""#,
         ),
     ],
+    network_settings_1_set_resolver_configuration => [
+        // MAC address
+        (
+            r#""macAddress": "[0-9a-f]{2}:[0-9a-f]{2}:[0-9a-f]{2}:[0-9a-f]{2}:[0-9a-f]{2}:[0-9a-f]{2}""#,
+            r#""macAddress": "01:23:45:67:89:ab""#,
+        ),
+        // IPv6 link-local (EUI-64, derived from MAC)
+        (
+            r#""address": "fe80::[0-9a-f]{4}:[0-9a-f]{4}:[0-9a-f]{4}:[0-9a-f]{4}""#,
+            r#""address": "fe80::0123:4567:89ab:cdef""#,
+        ),
+        // Hostname derived from MAC/serial
+        (
+            r#""hostname": "axis-[0-9a-f]{12}""#,
+            r#""hostname": "axis-0123456789ab""#,
+        ),
+        (
+            r#""staticHostname": "axis-[0-9a-f]{12}""#,
+            r#""staticHostname": "axis-0123456789ab""#,
+        ),
+        (
+            r#""identity": "axis-[0-9a-f]{12}""#,
+            r#""identity": "axis-0123456789ab""#,
+        ),
+    ],
+    ntp_1_get_ntp_info,
     basic_device_info_get_all_properties => [
         (
             r#""SocSerialNumber": "[0-9A-F]{8}-[0-9A-F]{8}-[0-9A-F]{8}-[0-9A-F]{8}""#,
@@ -230,20 +275,28 @@ cassette_tests! {
             r#"Name="DeviceUUID"><aev:Value>00000000-0000-0000-0123-456789abcdef</aev:Value>"#,
         ),
     ],
+    event1_pull_point_subscription,
+    firmware_management_1_purge_uncommitted,
+    firmware_management_1_status,
     firmware_management_1_upgrade_mismatch,
     parameter_management_list_error,
+    parameter_management_list_group,
     parameter_management_list_image_resolution,
     parameter_management_update_network_ssh_enabled,
     pwdgrp_add_user_already_exists,
     pwdgrp_add_user_invalid_password,
     pwdgrp_add_user_invalid_username,
     pwdgrp_remove_user_does_not_exist,
+    pwdgrp_update_user,
+    recording_group_1_crud,
     remote_object_storage_1_beta_crud,
+    remote_object_storage_1_beta_s3_crud,
     siren_and_light_2_alpha_maintenance_mode_not_supported,
     ssh_1_add_user_without_comment,
     ssh_1_crud,
     ssh_1_set_user_does_not_exist,
     ssh_1_set_user_validation_error,
+    ssh_2_crud,
     system_ready_1_system_ready => [
         (
             r#""uptime": "\d+""#,
@@ -254,6 +307,7 @@ cassette_tests! {
             r#""bootid": "00000000-0000-0000-0000-000000000000""#,
         ),
     ],
+    time_1_get_date_time_info,
 }
 
 fn record_trials(library: &Library) -> Vec<Trial> {
@@ -379,6 +433,54 @@ async fn action1_get_action_rules(client: &CassetteClient, _prelude: Option<Prel
     GetActionRulesRequest.send(client).await.unwrap();
 }
 
+async fn action1_get_action_templates(client: &CassetteClient, _prelude: Option<Prelude>) {
+    let templates = GetActionTemplatesRequest.send(client).await.unwrap();
+    let template = templates.find("com.axis.action.fixed.ledcontrol").unwrap();
+    let configuration_id = AddActionConfigurationRequest::from_template(template)
+        .param("led", "statusled")
+        .param("color", "green,none")
+        .param("duration", "1")
+        .param("interval", "250")
+        .send(client)
+        .await
+        .unwrap()
+        .configuration_id;
+    RemoveActionConfigurationRequest::new(configuration_id)
+        .send(client)
+        .await
+        .unwrap();
+}
+
+async fn action1_recipient_configuration_crud(client: &CassetteClient, _prelude: Option<Prelude>) {
+    let templates = GetRecipientTemplatesRequest.send(client).await.unwrap();
+    let template = templates.find("com.axis.recipient.https").unwrap();
+
+    let mut request = AddRecipientConfigurationRequest::from_template(template).name("cassette");
+    for parameter in &template.parameters.parameter {
+        let value = match parameter.name.as_str() {
+            "upload_url" => "https://192.0.2.1/hook",
+            _ => "",
+        };
+        request = request.param(&parameter.name, value);
+    }
+    let configuration_id = request.send(client).await.unwrap().configuration_id;
+
+    let configurations = GetRecipientConfigurationsRequest
+        .send(client)
+        .await
+        .unwrap()
+        .recipient_configurations
+        .recipient_configuration;
+    assert!(configurations
+        .iter()
+        .any(|c| c.configuration_id == configuration_id));
+
+    RemoveRecipientConfigurationRequest::new(configuration_id)
+        .send(client)
+        .await
+        .unwrap();
+}
+
 async fn add_status_led_configuration(client: &CassetteClient) -> u16 {
     AddActionConfigurationRequest::new("com.axis.action.fixed.ledcontrol")
         .param("led", "statusled")
@@ -684,6 +786,43 @@ async fn action1_remove_action_configuration_in_use(
         .unwrap();
 }
 
+async fn cert_create_self_signed_certificate(client: &CassetteClient, _: Option<Prelude>) {
+    use rs4a_vapix::apis::cert::{
+        CreateSelfSignedCertificateRequest, DeleteCertificatesRequest, GetCACertificatesRequest,
+        GetCertificatesRequest,
+    };
+
+    GetCACertificatesRequest::new().send(client).await.unwrap();
+    let before = GetCertificatesRequest::new().send(client).await.unwrap();
+    assert!(before.find("rs4a_test").is_none());
+
+    CreateSelfSignedCertificateRequest::new("rs4a_test", "rs4a-test.local")
+        .send(client)
+        .await
+        .unwrap();
+    let after = GetCertificatesRequest::new().send(client).await.unwrap();
+    assert!(after.find("rs4a_test").is_some());
+
+    DeleteCertificatesRequest::new("rs4a_test")
+        .send(client)
+        .await
+        .unwrap();
+}
+
+async fn applications_list(client: &CassetteClient, _: Option<Prelude>) {
+    use rs4a_vapix::apis::applications::{ControlRequest, ErrorKind, ListRequest};
+
+    let data = ListRequest::new().send(client).await.unwrap();
+    assert!(data.find("no_such_application").is_none());
+
+    let error = ControlRequest::start("no_such_application")
+        .send(client)
+        .await
+        .unwrap_err()
+        .unwrap_service();
+    assert_eq!(ErrorKind::try_from(error.code), Ok(ErrorKind::NotFound));
+}
+
 async fn api_discovery_1_get_api_list(client: &CassetteClient, _: Option<Prelude>) {
     use rs4a_vapix::apis::{api_discovery_1::GetApiListRequest, network_settings_1};
 
@@ -874,6 +1013,64 @@ async fn event1_get_event_instances(client: &CassetteClient, _prelude: Option<Pr
     assert!(!data.message_instances.is_empty());
 }
 
+async fn event1_pull_point_subscription(client: &CassetteClient, _prelude: Option<Prelude>) {
+    let subscription = CreatePullPointSubscriptionRequest::new()
+        .topic_filter("tns1:Device/tnsaxis:Status/SystemReady")
+        .initial_termination_time(Duration::from_secs(60))
+        .send(client)
+        .await
+        .unwrap()
+        .subscription_reference;
+
+    // Property events are reported with their initial state when the subscription is created.
+    let notifications = PullMessagesRequest::new(&subscription)
+        .timeout(Duration::from_secs(5))
+        .send(client)
+        .await
+        .unwrap()
+        .notifications;
+    let notification = notifications
+        .iter()
+        .find(|n| n.topic_path() == ["Device", "Status", "SystemReady"])
+        .expect("SystemReady should be reported when subscribing");
+    assert_eq!(
+        notification.property_operation,
+        Some(PropertyOperation::Initialized)
+    );
+    assert_eq!(notification.data_value("ready"), Some("1"));
+
+    RenewRequest::new(&subscription, Duration::from_secs(60))
+        .send(client)
+        .await
+        .unwrap();
+
+    UnsubscribeRequest::new(&subscription)
+        .send(client)
+        .await
+        .unwrap();
+}
+
+async fn firmware_management_1_status(client: &CassetteClient, _prelude: Option<Prelude>) {
+    let data = StatusRequest::new().send(client).await.unwrap();
+    assert!(!data.active_firmware_version.is_empty());
+}
+
+async fn firmware_management_1_purge_uncommitted(
+    client: &CassetteClient,
+    _prelude: Option<Prelude>,
+) {
+    let status = StatusRequest::new().send(client).await.unwrap();
+    if status.is_committed {
+        return;
+    }
+    let error = PurgeRequest::new().send(client).await.unwrap_err();
+    let error = error.unwrap_service();
+    assert_eq!(
+        firmware_management_1::ErrorKind::try_from(error.code),
+        Ok(firmware_management_1::ErrorKind::UncommittedFirmware),
+    );
+}
+
 // This normally happens if the firmware is for a different device model.
 // Apparently it also happens with an invalid firmware binary.
 async fn firmware_management_1_upgrade_mismatch(
@@ -917,6 +1114,16 @@ async fn parameter_management_list_error(client: &CassetteClient, prelude: Optio
     assert!(error.to_string().contains("-1"));
 }
 
+async fn parameter_management_list_group(client: &CassetteClient, _prelude: Option<Prelude>) {
+    let params = ListRequest::group("root.Brand").send(client).await.unwrap();
+    let product_number = params.parse::<BrandProdNbr>().unwrap().unwrap();
+    let tree = params.tree();
+    assert_eq!(
+        tree.get("root.Brand.ProdNbr").unwrap().value(),
+        Some(product_number.as_str())
+    );
+}
+
 async fn parameter_management_list_image_resolution(
     client: &CassetteClient,
     prelude: Option<Prelude>,
@@ -995,6 +1202,34 @@ async fn pwdgrp_add_user_already_exists(client: &CassetteClient, _prelude: Optio
     RemoveUserRequest::new(username).send(client).await.unwrap();
 }
 
+async fn pwdgrp_update_user(client: &CassetteClient, _prelude: Option<Prelude>) {
+    use rs4a_vapix::apis::pwdgrp::{
+        AddUserRequest, GetUsersRequest, Group, RemoveUserRequest, Role, UpdateUserRequest,
+    };
+
+    let username = "cassette_test_update";
+    AddUserRequest::new(username, "Good morning", Group::Users, Role::Viewer)
+        .send(client)
+        .await
+        .unwrap();
+
+    UpdateUserRequest::new(username)
+        .password("Good & proper")
+        .role(Role::OperatorViewer)
+        .send(client)
+        .await
+        .unwrap();
+
+    let data = GetUsersRequest::new().send(client).await.unwrap();
+    let user = data.find(username).unwrap();
+    assert_eq!(user.group, Some(Group::Users));
+    assert_eq!(user.role, Some(Role::OperatorViewer));
+
+    RemoveUserRequest::new(username).send(client).await.unwrap();
+    let data = GetUsersRequest::new().send(client).await.unwrap();
+    assert_eq!(data.find(username), None);
+}
+
 async fn pwdgrp_add_user_invalid_password(client: &CassetteClient, _prelude: Option<Prelude>) {
     use rs4a_vapix::apis::pwdgrp::{AddUserRequest, Group, Role};
 
@@ -1031,6 +1266,74 @@ async fn pwdgrp_remove_user_does_not_exist(client: &CassetteClient, _prelude: Op
     assert_eq!(error.message(), "account user name");
 }
 
+async fn recording_group_1_crud(client: &CassetteClient, prelude: Option<Prelude>) {
+    if let Some(prelude) = prelude {
+        if !prelude.supports_device_config() {
+            return;
+        }
+    }
+
+    let destination_id = DestinationId::new("my_recording_destination_id".to_string());
+    CreateDestinationRequest::azure(
+        destination_id.clone(),
+        AzureDestination::new(
+            "my-container".to_string(),
+            "my-sas".to_string(),
+            Url::parse("https://example.blob.core.windows.net").unwrap(),
+        ),
+    )
+    .send(client)
+    .await
+    .unwrap();
+
+    // Create
+    let created = CreateRecordingGroupsRequest::new()
+        .nice_name("my-nice-name".to_string())
+        .destination(Destination::remote_object_storage(destination_id.clone()))
+        .send(client)
+        .await
+        .unwrap();
+    assert_eq!(created.nice_name, "my-nice-name");
+
+    // List
+    let all = ListRecordingGroupsRequest::new()
+        .send(client)
+        .await
+        .unwrap();
+    assert!(all.iter().any(|g| g.id == created.id));
+
+    // Update
+    let () = UpdateRecordingGroupRequest::description(
+        created.id.clone(),
+        "my-updated-description".to_string(),
+    )
+    .send(client)
+    .await
+    .unwrap();
+    let updated = GetRecordingGroupRequest::new(created.id.clone())
+        .send(client)
+        .await
+        .unwrap();
+    assert_eq!(updated.description, "my-updated-description");
+
+    // Delete
+    let () = DeleteRecordingGroupRequest::new(created.id.clone())
+        .send(client)
+        .await
+        .unwrap();
+    let error = GetRecordingGroupRequest::new(created.id)
+        .send(client)
+        .await
+        .unwrap_err()
+        .unwrap_service();
+    assert_eq!(error.kind().unwrap(), ErrorKind::NotFound);
+
+    DeleteDestinationRequest::new(destination_id)
+        .send(client)
+        .await
+        .unwrap();
+}
+
 async fn remote_object_storage_1_beta_crud(client: &CassetteClient, prelude: Option<Prelude>) {
     if let Some(prelude) = prelude {
         if !prelude.supports_device_config() {
@@ -1054,7 +1357,7 @@ async fn remote_object_storage_1_beta_crud(client: &CassetteClient, prelude: Opt
     .await
     .unwrap();
     assert_eq!(&created.id, &id);
-    assert!(created.azure.is_some());
+    assert!(created.azure().is_some());
 
     // List
     let all = ListDestinationsRequest::new().send(client).await.unwrap();
@@ -1073,7 +1376,7 @@ async fn remote_object_storage_1_beta_crud(client: &CassetteClient, prelude: Opt
         created.id.clone(),
         AzureDestination {
             sas: Some("my-updated-sas".to_string()),
-            ..created.azure.unwrap()
+            ..created.azure().unwrap().clone()
         },
     )
     .send(client)
@@ -1101,6 +1404,60 @@ async fn remote_object_storage_1_beta_crud(client: &CassetteClient, prelude: Opt
     assert!(!all.iter().any(|d| d.id == created.id));
 }
 
+async fn remote_object_storage_1_beta_s3_crud(client: &CassetteClient, prelude: Option<Prelude>) {
+    if let Some(prelude) = prelude {
+        if !prelude.supports_device_config() {
+            return;
+        }
+    }
+
+    let id = DestinationId::new("my_s3_destination_id".to_string());
+
+    let created = CreateDestinationRequest::s3(
+        id.clone(),
+        S3Destination::new(
+            "my-bucket".to_string(),
+            Url::parse("http://192.168.0.10:9000").unwrap(),
+            "my-access-key-id".to_string(),
+            "my-secret-access-key".to_string(),
+        ),
+    )
+    .send(client)
+    .await
+    .unwrap();
+    assert_eq!(created.s3().unwrap().bucket, "my-bucket");
+
+    let () = UpdateDestinationRequest::s3(
+        id.clone(),
+        S3Destination::new(
+            "my-bucket".to_string(),
+            Url::parse("http://192.168.0.10:9000").unwrap(),
+            "my-access-key-id".to_string(),
+            "my-secret-access-key".to_string(),
+        )
+        .region("eu-north-1".to_string()),
+    )
+    .send(client)
+    .await
+    .unwrap();
+    let updated = GetDestinationRequest::new(id.clone())
+        .send(client)
+        .await
+        .unwrap();
+    assert_eq!(updated.s3().unwrap().region.as_deref(), Some("eu-north-1"));
+
+    let () = DeleteDestinationRequest::new(id.clone())
+        .send(client)
+        .await
+        .unwrap();
+    let error = GetDestinationRequest::new(id)
+        .send(client)
+        .await
+        .unwrap_err()
+        .unwrap_service();
+    assert_eq!(error.kind().unwrap(), ErrorKind::NotFound);
+}
+
 // TODO: Figure out why these recordings are inconsistent
 async fn siren_and_light_2_alpha_maintenance_mode_not_supported(
     client: &CassetteClient,
@@ -1185,6 +1542,47 @@ async fn ssh_1_crud(client: &CassetteClient, prelude: Option<Prelude>) {
     DeleteUserRequest::new(username).send(client).await.unwrap();
 }
 
+async fn ssh_2_crud(client: &CassetteClient, prelude: Option<Prelude>) {
+    use rs4a_vapix::apis::{
+        discover::DiscoverRequest,
+        ssh_2::{AddUserRequest, DeleteUserRequest, ListUsersRequest, SetUserRequest},
+    };
+
+    if let Some(prelude) = &prelude {
+        if !prelude.supports_device_config() {
+            return;
+        }
+    }
+    let data = DiscoverRequest.send(client).await.unwrap();
+    if !data.supports("ssh", "v2") {
+        return;
+    }
+
+    let username = "dalliard";
+    let key = "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIHN2fyAvCsH6dq5ycVPsE2RkEj6QZ6/Rw3TbQlQfmY0x";
+
+    AddUserRequest::new(username)
+        .comment("Good morning")
+        .authorized_key(key)
+        .send(client)
+        .await
+        .unwrap();
+
+    let users = ListUsersRequest::new().send(client).await.unwrap();
+    let user = users.iter().find(|u| u.username == username).unwrap();
+    assert_eq!(user.authorized_keys, [key]);
+
+    SetUserRequest::new(username)
+        .authorized_keys(Vec::new())
+        .send(client)
+        .await
+        .unwrap();
+
+    DeleteUserRequest::new(username).send(client).await.unwrap();
+    let users = ListUsersRequest::new().send(client).await.unwrap();
+    assert!(!users.iter().any(|u| u.username == username));
+}
+
 async fn ssh_1_set_user_does_not_exist(client: &CassetteClient, prelude: Option<Prelude>) {
     use rs4a_vapix::apis::ssh_1::SetUserRequest;
     if let Some(prelude) = &prelude {
@@ -1291,6 +1689,67 @@ async fn network_settings_1_set_global_proxy_configuration(
     assert_eq!(restored.no_proxy, initial.no_proxy);
 }
 
+async fn network_settings_1_set_resolver_configuration(
+    client: &CassetteClient,
+    _prelude: Option<Prelude>,
+) {
+    let read = || async {
+        let data = GetNetworkInfoRequest::new().send(client).await.unwrap();
+        data.system.resolver
+    };
+
+    let initial = read().await;
+
+    SetResolverConfigurationRequest::from_resolver(&initial)
+        .static_search_domains(vec!["example.com".to_string()])
+        .send(client)
+        .await
+        .unwrap();
+
+    let updated = read().await;
+    assert_eq!(updated.static_search_domains, ["example.com"]);
+    assert_eq!(updated.static_name_servers, initial.static_name_servers);
+
+    SetResolverConfigurationRequest::from_resolver(&initial)
+        .send(client)
+        .await
+        .unwrap();
+
+    let restored = read().await;
+    assert_eq!(
+        restored.static_search_domains,
+        initial.static_search_domains
+    );
+}
+
+async fn ntp_1_get_ntp_info(client: &CassetteClient, prelude: Option<Prelude>) {
+    use rs4a_vapix::apis::ntp_1::{GetNtpInfoRequest, API_ID};
+
+    if let Some(prelude) = prelude {
+        if !prelude.is_supported(API_ID, ">=1") {
+            return;
+        }
+    }
+
+    let data = GetNtpInfoRequest::new().send(client).await.unwrap();
+    if data.client.enabled {
+        assert!(!data.client.servers.is_empty());
+    }
+}
+
+async fn time_1_get_date_time_info(client: &CassetteClient, prelude: Option<Prelude>) {
+    use rs4a_vapix::apis::time_1::{GetDateTimeInfoRequest, API_ID};
+
+    if let Some(prelude) = prelude {
+        if !prelude.is_supported(API_ID, ">=1") {
+            return;
+        }
+    }
+
+    let data = GetDateTimeInfoRequest::new().send(client).await.unwrap();
+    assert!(!data.posix_time_zone.is_empty());
+}
+
 async fn system_ready_1_system_ready(client: &CassetteClient, _prelude: Option<Prelude>) {
     let data = SystemReadyRequest::new().send(client).await.unwrap();
     assert!(data.systemready);
@@ -5,16 +5,26 @@ use rs4a_vapix::{
     apis::{
         action1::{
             AddActionConfigurationRequest, AddActionConfigurationResponse, AddActionRuleRequest,
-            Condition, GetActionConfigurationsRequest, GetActionRulesRequest,
+            AddRecipientConfigurationRequest, Condition, GetActionConfigurationsRequest,
+            GetActionRulesRequest,
         },
         basic_device_info_1,
         basic_device_info_1::{AllPropertiesData, AllUnrestrictedPropertiesData, Architecture},
+        event1::CreatePullPointSubscriptionRequest,
         firmware_management_1,
-        firmware_management_1::UpgradeData,
+        firmware_management_1::{RollbackData, StatusData, UpgradeData},
+        ntp_1::{NtpInfoData, ServersSource},
+        recording_group_1::{
+            ContainerFormat, CreateRecordingGroupsRequest, Destination, RecordingGroupData,
+        },
+        remote_object_storage_1_beta::{DestinationData, DestinationId, DestinationKind},
+        ssh_2::User,
         system_ready_1::SystemreadyData,
+        time_1::DateTimeInfoData,
     },
     protocol_helpers::{
         json_rpc::{parse_data, parse_data_lossless},
+        rest,
         soap::parse_soap,
     },
 };
@@ -63,6 +73,109 @@ fn can_deserialize_firmware_management_1_examples() {
         firmware_management_1::ErrorKind::try_from(error.code),
         Ok(firmware_management_1::ErrorKind::DowngradeNotAllowed),
     );
+
+    let text = include_str!("../src/apis/axis_cgi/firmware_management_1/status_1_0.json");
+    let data = parse_data_lossless::<StatusData>(text).unwrap().unwrap();
+    assert!(!data.is_committed);
+    assert_eq!(data.inactive_firmware_version.as_deref(), Some("12.8.63"));
+    assert_eq!(data.time_to_rollback, Some(900));
+
+    let text = include_str!("../src/apis/axis_cgi/firmware_management_1/status_committed_1_0.json");
+    let data = parse_data_lossless::<StatusData>(text).unwrap().unwrap();
+    assert!(data.is_committed);
+    assert_eq!(data.inactive_firmware_version, None);
+    let text = text.replace("isCommited", "isCommitted");
+    let data = parse_data::<StatusData>(&text).unwrap().unwrap();
+    assert!(data.is_committed);
+
+    let text = include_str!("../src/apis/axis_cgi/firmware_management_1/rollback_1_0.json");
+    let data = parse_data_lossless::<RollbackData>(text).unwrap().unwrap();
+    assert_eq!(data.firmware_version, "12.8.63");
+
+    let text =
+        include_str!("../src/apis/axis_cgi/firmware_management_1/rollback_404_error_1_0.json");
+    let error = parse_data_lossless::<RollbackData>(text)
+        .unwrap()
+        .unwrap_err();
+    assert_eq!(
+        firmware_management_1::ErrorKind::try_from(error.code),
+        Ok(firmware_management_1::ErrorKind::NoPreviousFirmware),
+    );
+
+    let text = include_str!("../src/apis/axis_cgi/firmware_management_1/purge_412_error_1_0.json");
+    let error = parse_data_lossless::<firmware_management_1::PurgeData>(text)
+        .unwrap()
+        .unwrap_err();
+    assert_eq!(
+        firmware_management_1::ErrorKind::try_from(error.code),
+        Ok(firmware_management_1::ErrorKind::UncommittedFirmware),
+    );
+}
+
+#[test]
+fn can_deserialize_ntp_1_examples() {
+    let text = include_str!("../src/apis/axis_cgi/ntp_1/get_ntp_info_1_0.json");
+    let data = parse_data_lossless::<NtpInfoData>(text).unwrap().unwrap();
+    assert_eq!(data.client.servers_source, ServersSource::Static);
+    assert_eq!(data.client.static_servers, ["192.168.0.1"]);
+    assert_eq!(data.time_in_sync, Some(true));
+}
+
+#[test]
+fn can_deserialize_recording_group_1_examples() {
+    let text = include_str!("../src/apis/config/recording_group_1/list_200.json");
+    let groups = rest::parse_data_lossless::<Vec<RecordingGroupData>>(text)
+        .unwrap()
+        .unwrap();
+    assert_eq!(groups.len(), 1);
+    let group = groups.first().unwrap();
+    assert_eq!(group.container_format, ContainerFormat::Cmaf);
+    assert_eq!(
+        group
+            .destinations
+            .first()
+            .unwrap()
+            .remote_object_storage
+            .id
+            .as_str(),
+        "my_destination_id"
+    );
+
+    let text = include_str!("../src/apis/config/recording_group_1/get_404_error.json");
+    let error = rest::parse_data_lossless::<RecordingGroupData>(text)
+        .unwrap()
+        .unwrap_err();
+    assert_eq!(error.kind(), Some(rest::ErrorKind::NotFound));
+}
+
+#[test]
+fn can_deserialize_remote_object_storage_1_beta_examples() {
+    let text = include_str!("../src/apis/config/remote_object_storage_1_beta/list_200.json");
+    let destinations = rest::parse_data_lossless::<Vec<DestinationData>>(text)
+        .unwrap()
+        .unwrap();
+    let [azure, s3] = destinations.as_slice() else {
+        panic!("Expected two destinations but got {destinations:#?}");
+    };
+    assert!(matches!(azure.kind, DestinationKind::Azure(_)));
+    let s3 = s3.s3().unwrap();
+    assert_eq!(s3.bucket, "recordings");
+    assert_eq!(s3.region.as_deref(), Some("us-east-1"));
+    assert_eq!(s3.access_key_id, None);
+}
+
+#[test]
+fn can_deserialize_ssh_2_examples() {
+    let text = include_str!("../src/apis/config/ssh_2/list_users_200.json");
+    let users = rest::parse_data_lossless::<Vec<User>>(text)
+        .unwrap()
+        .unwrap();
+    let [ssh, maintainer] = users.as_slice() else {
+        panic!("Expected two users but got {users:#?}");
+    };
+    assert_eq!(ssh.authorized_keys.len(), 1);
+    assert_eq!(maintainer.comment, None);
+    assert!(maintainer.authorized_keys.is_empty());
 }
 
 #[test]
@@ -82,6 +195,16 @@ fn can_deserialize_system_ready_1_preview_mode() {
     );
 }
 
+#[test]
+fn can_deserialize_time_1_examples() {
+    let text = include_str!("../src/apis/axis_cgi/time_1/get_date_time_info_1_0.json");
+    let data = parse_data_lossless::<DateTimeInfoData>(text)
+        .unwrap()
+        .unwrap();
+    assert_eq!(data.date_time.to_rfc3339(), "2024-03-01T09:06:18+00:00");
+    assert_eq!(data.time_zone.as_deref(), Some("Europe/Stockholm"));
+}
+
 #[test]
 fn can_serialize_action_1_requests() {
     expect_file!["./snapshots/add_action_configuration.xml"].assert_eq(
@@ -103,8 +226,47 @@ fn can_serialize_action_1_requests() {
             })
             .into_envelope(),
     );
+    expect_file!["./snapshots/add_recipient_configuration.xml"].assert_eq(
+        &AddRecipientConfigurationRequest::new("com.axis.recipient.https")
+            .name("Webhook")
+            .param("upload_url", "https://example.com/hook?a=1&b=2")
+            .param("login", "")
+            .param("password", "")
+            .param("proxy_host", "")
+            .param("validate_server_cert", "1")
+            .try_into_envelope()
+            .unwrap(),
+    );
     expect_file!["./snapshots/get_action_configurations.xml"]
         .assert_eq(&GetActionConfigurationsRequest::new().into_envelope());
     expect_file!["./snapshots/get_action_rules.xml"]
         .assert_eq(&GetActionRulesRequest::new().into_envelope());
 }
+
+#[test]
+fn can_serialize_recording_group_1_requests() {
+    let request = CreateRecordingGroupsRequest::new()
+        .nice_name("Edge storage test".to_string())
+        .container_format(ContainerFormat::Matroska)
+        .destination(Destination::remote_object_storage(DestinationId::new(
+            "my_destination_id".to_string(),
+        )))
+        .segment_duration(10, 20)
+        .pre_duration(5)
+        .post_duration(5)
+        .into_request();
+    expect_file!["./snapshots/create_recording_group.json"].assert_eq(
+        std::str::from_utf8(request.body.as_ref().unwrap().as_bytes().unwrap()).unwrap(),
+    );
+}
+
+#[test]
+fn can_serialize_event_1_requests() {
+    expect_file!["./snapshots/create_pull_point_subscription.xml"].assert_eq(
+        &CreatePullPointSubscriptionRequest::new()
+            .topic_filter("tns1:Device/tnsaxis:IO/VirtualInput")
+            .topic_filter("tns1:Device/tnsaxis:Status/SystemReady")
+            .initial_termination_time(Duration::from_secs(60))
+            .into_envelope(),
+    );
+}
@@ -8,14 +8,22 @@ use rs4a_vapix::{
             GetActionConfigurationsRequest, GetActionRulesRequest,
         },
         event1::GetEventInstancesRequest,
-        recording_group_1::CreateRecordingGroupsRequest,
-        remote_object_storage_1_beta::{CreateDestinationRequest, DestinationId, S3Destination},
+        event_stream_1::SubscribeRequest,
+        recording_group_1::{
+            CreateRecordingGroupsRequest, DeleteRecordingGroupRequest, Destination,
+            GetRecordingGroupRequest,
+        },
+        remote_object_storage_1_beta::{
+            CreateDestinationRequest, DeleteDestinationRequest, DestinationId, S3Destination,
+        },
+        server_report::{ArchiveMode, DownloadServerReportRequest, GetServerReportRequest},
+        system_log::GetSystemLogRequest,
         system_ready_1::SystemReadyRequest,
     },
-    requests::jpg_3::GetImageRequest,
+    requests::jpg_3::{GetImageRequest, GetMjpegStreamRequest},
     Client, ClientBuilder,
 };
-use serde_json::json;
+use url::Url;
 
 async fn test_client() -> Option<Client> {
     let _ = env_logger::Builder::new()
@@ -105,6 +113,21 @@ async fn event_1_get_event_instances_returns_ok() {
     GetEventInstancesRequest::new().send(&client).await.unwrap();
 }
 
+#[tokio::test]
+async fn event_stream_1_subscribe_returns_ok() {
+    let Some(client) = test_client().await else {
+        return;
+    };
+    SubscribeRequest::new()
+        .topic_filter("tns1:Device/tnsaxis:Status/SystemReady")
+        .send(&client)
+        .await
+        .unwrap()
+        .close()
+        .await
+        .unwrap();
+}
+
 #[tokio::test]
 async fn jpg_get_image_returns_ok() {
     let Some(client) = test_client().await else {
@@ -117,6 +140,22 @@ async fn jpg_get_image_returns_ok() {
         .unwrap();
 }
 
+#[tokio::test]
+async fn jpg_get_mjpeg_stream_yields_frames() {
+    let Some(client) = test_client().await else {
+        return;
+    };
+    let mut stream = GetMjpegStreamRequest::new()
+        .camera(1)
+        .fps(5)
+        .send(&client)
+        .await
+        .unwrap();
+    for _ in 0..2 {
+        stream.next_frame().await.unwrap().unwrap();
+    }
+}
+
 #[tokio::test]
 async fn recording_group_1_create_returns_ok() {
     let Some(client) = test_client().await else {
@@ -128,14 +167,13 @@ async fn recording_group_1_create_returns_ok() {
 
     let actual_recording_destination_id = CreateDestinationRequest::s3(
         DestinationId::new(expected_recording_destination_id.clone()),
-        S3Destination {
-            bucket: "myBucket".to_string(),
-            region: None,
-            url: "https://s3.eu-north-1.amazonaws.com".to_string(),
-            access_key_id: Some("myAccessKeyId".to_string()),
-            secret_access_key: Some("mySecretAccessKey".to_string()),
-            session_token: None,
-        },
+        S3Destination::new(
+            "myBucket".to_string(),
+            Url::parse("https://s3.eu-north-1.amazonaws.com").unwrap(),
+            "myAccessKeyId".to_string(),
+            "mySecretAccessKey".to_string(),
+        )
+        .region("eu-north-1".to_string()),
     )
     .send(&client)
     .await
@@ -144,17 +182,28 @@ async fn recording_group_1_create_returns_ok() {
 
     assert_eq!(
         expected_recording_destination_id,
-        actual_recording_destination_id.into_string()
+        actual_recording_destination_id.as_str()
     );
 
-    CreateRecordingGroupsRequest::new()
-        .data(json!({
-            "destinations": [{
-                "remoteObjectStorage":  {
-                    "id": expected_recording_destination_id
-                },
-            }],
-        }))
+    let group = CreateRecordingGroupsRequest::new()
+        .destination(Destination::remote_object_storage(
+            actual_recording_destination_id.clone(),
+        ))
+        .send(&client)
+        .await
+        .unwrap();
+
+    let fetched = GetRecordingGroupRequest::new(group.id.clone())
+        .send(&client)
+        .await
+        .unwrap();
+    assert_eq!(fetched, group);
+
+    DeleteRecordingGroupRequest::new(group.id)
+        .send(&client)
+        .await
+        .unwrap();
+    DeleteDestinationRequest::new(actual_recording_destination_id)
         .send(&client)
         .await
         .unwrap();
@@ -167,3 +216,29 @@ async fn system_ready_system_ready_returns_ok() {
     };
     SystemReadyRequest::new().send(&client).await.unwrap();
 }
+
+#[tokio::test]
+async fn server_report_returns_ok() {
+    let Some(client) = test_client().await else {
+        return;
+    };
+    let report = GetServerReportRequest::new().send(&client).await.unwrap();
+    assert!(!report.is_empty());
+
+    let path = std::env::temp_dir().join(somewhat_unique_name("smoke_test_server_report_"));
+    let len = DownloadServerReportRequest::new(ArchiveMode::TarAll)
+        .download(&client, &path)
+        .await
+        .unwrap();
+    assert_eq!(std::fs::metadata(&path).unwrap().len(), len);
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[tokio::test]
+async fn system_log_returns_ok() {
+    let Some(client) = test_client().await else {
+        return;
+    };
+    let log = GetSystemLogRequest::new().send(&client).await.unwrap();
+    assert!(log.lines.iter().any(|l| l.timestamp.is_some()));
+}
@@ -386,6 +386,11 @@ impl Loan {
     pub fn ssh_port(&self) -> u16 {
         self.loanable.internal_ip.ssh_port()
     }
+
+    /// Returns the port forwarded to port 554.
+    pub fn rtsp_port(&self) -> u16 {
+        self.loanable.internal_ip.rtsp_port()
+    }
 }
 
 #[derive(Clone, Copy, Debug, Deserialize, Serialize)]